@@ -0,0 +1,82 @@
+//! Command-line overrides for the initial settings.
+//!
+//! Anything given here takes precedence over the saved config, so scripted
+//! launches behave the same regardless of what was last used in the GUI.
+//! The overrides last for the launch only and are never saved.
+
+use clap::Parser;
+use osc_sender::osc_core::{INTERVAL_RANGE, Settings, is_valid_address};
+
+use crate::HOLD_RANGE;
+
+#[derive(Clone, Parser)]
+#[command(version, about = "Sends repeated OSC button presses to VRChat")]
+pub struct Cli {
+    /// Destination port
+    #[arg(long)]
+    pub port: Option<u16>,
+
+    /// Click interval in milliseconds
+    #[arg(long, value_parser = clap::value_parser!(u64).range(*INTERVAL_RANGE.start()..=*INTERVAL_RANGE.end()))]
+    pub interval_ms: Option<u64>,
+
+    /// Hold duration in milliseconds
+    #[arg(long, value_parser = clap::value_parser!(u64).range(*HOLD_RANGE.start()..=*HOLD_RANGE.end()))]
+    pub hold_ms: Option<u64>,
+
+    /// OSC address to send to, e.g. /input/Jump
+    #[arg(long, value_parser = parse_address)]
+    pub address: Option<String>,
+
+    /// Start sending as soon as the window opens, overriding the saved
+    /// auto-start option
+    #[arg(long, value_name = "BOOL")]
+    pub auto_start: Option<bool>,
+
+    /// Run without a window, sending immediately until Ctrl-C
+    #[arg(long)]
+    pub headless: bool,
+}
+
+impl Cli {
+    pub fn apply(&self, settings: &mut Settings) {
+        if let Some(port) = self.port {
+            settings.dest_ports = vec![port];
+        }
+        if let Some(interval_ms) = self.interval_ms {
+            settings.interval_ms = interval_ms;
+        }
+        if let Some(hold_ms) = self.hold_ms {
+            settings.hold_ms = hold_ms;
+        }
+        if let Some(address) = &self.address {
+            settings.address = address.clone();
+        }
+    }
+
+    /// Undoes [`Self::apply`] before saving: every overridden field still at
+    /// its command-line value goes back to what was `loaded`. Fields changed
+    /// in the window since keep the change.
+    pub fn unapply(&self, settings: &mut Settings, loaded: &Settings) {
+        if self.port.is_some_and(|port| settings.dest_ports == [port]) {
+            settings.dest_ports.clone_from(&loaded.dest_ports);
+        }
+        if self.interval_ms == Some(settings.interval_ms) {
+            settings.interval_ms = loaded.interval_ms;
+        }
+        if self.hold_ms == Some(settings.hold_ms) {
+            settings.hold_ms = loaded.hold_ms;
+        }
+        if self.address.as_ref() == Some(&settings.address) {
+            settings.address.clone_from(&loaded.address);
+        }
+    }
+}
+
+fn parse_address(value: &str) -> Result<String, String> {
+    if is_valid_address(value) {
+        Ok(value.to_string())
+    } else {
+        Err("must start with '/' and contain no whitespace".to_string())
+    }
+}
@@ -0,0 +1,365 @@
+//! Loading and saving of the persistent config file.
+//!
+//! The config is stored as JSON in the OS config directory. A missing or
+//! unreadable file is never fatal: the defaults are used instead.
+
+use std::fs;
+use std::io;
+use std::net::IpAddr;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+
+use directories::ProjectDirs;
+use osc_sender::osc_core::{INTERVAL_RANGE, Settings, is_valid_address};
+use serde::{Deserialize, Serialize};
+
+use crate::hotkey::{DEFAULT_STOP_HOTKEY, DEFAULT_TOGGLE_HOTKEY};
+use crate::i18n::{Language, Strings};
+use crate::{
+    BURST_PAUSE_RANGE, HEARTBEAT_INTERVAL_RANGE, HOLD_RANGE, JITTER_STD_DEV_RANGE,
+    MULTICAST_TTL_RANGE, START_DELAY_RANGE, SWEEP_PERIOD_RANGE, UI_SCALE_RANGE,
+};
+
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    #[serde(flatten)]
+    pub settings: Settings,
+    /// Extra senders, each shown as its own tab and running alongside the
+    /// main settings.
+    pub profiles: Vec<Profile>,
+    pub presets: Vec<Preset>,
+    pub interval_in_hz: bool,
+    pub quick_launcher_template: String,
+    pub toggle_hotkey: String,
+    /// Global hotkey that stops sending and releases immediately.
+    pub stop_hotkey: String,
+    /// Global hotkey that sends only while held; empty for none.
+    pub hold_hotkey: String,
+    pub always_on_top: bool,
+    pub theme: Theme,
+    pub language: Language,
+    /// Zoom applied on top of the OS display scale.
+    pub ui_scale: f32,
+    /// The Advanced section is expanded.
+    pub advanced_open: bool,
+    /// Show only the send toggle and a status dot in a small window.
+    pub compact: bool,
+    /// Non-loopback destinations that no longer ask for confirmation before
+    /// sending starts.
+    pub trusted_ips: Vec<IpAddr>,
+    /// Destination hostnames trusted the same way.
+    pub trusted_hosts: Vec<String>,
+    /// Closing the window hides it to the tray instead of quitting. Only
+    /// has an effect in builds with the `tray` feature.
+    pub close_to_tray: bool,
+    /// Name of the MIDI input device whose notes click; empty for none.
+    /// Only has an effect in builds with the `midi` feature.
+    pub midi_device: String,
+    /// Notes that click; empty for any note.
+    pub midi_notes: Vec<u8>,
+    /// A Note-On only presses and its Note-Off releases, instead of each
+    /// Note-On being a whole click.
+    pub midi_note_off_release: bool,
+    /// Start sending as soon as the window opens.
+    pub auto_start: bool,
+    pub stats: Stats,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            settings: Settings::default(),
+            profiles: Vec::new(),
+            presets: Vec::new(),
+            interval_in_hz: false,
+            quick_launcher_template: "{port}:localhost:{listen_port}".to_string(),
+            toggle_hotkey: DEFAULT_TOGGLE_HOTKEY.to_string(),
+            stop_hotkey: DEFAULT_STOP_HOTKEY.to_string(),
+            hold_hotkey: String::new(),
+            always_on_top: false,
+            theme: Theme::default(),
+            language: Language::default(),
+            ui_scale: 1.0,
+            advanced_open: false,
+            compact: false,
+            trusted_ips: Vec::new(),
+            trusted_hosts: Vec::new(),
+            close_to_tray: false,
+            midi_device: String::new(),
+            midi_notes: Vec::new(),
+            midi_note_off_release: false,
+            auto_start: false,
+            stats: Stats::default(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Theme {
+    /// Follow the OS light/dark setting.
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+impl Theme {
+    pub fn preference(self) -> egui::ThemePreference {
+        match self {
+            Theme::System => egui::ThemePreference::System,
+            Theme::Light => egui::ThemePreference::Light,
+            Theme::Dark => egui::ThemePreference::Dark,
+        }
+    }
+
+    /// The theme the top bar button switches to next, in a loop.
+    pub fn next(self) -> Self {
+        match self {
+            Theme::System => Theme::Light,
+            Theme::Light => Theme::Dark,
+            Theme::Dark => Theme::System,
+        }
+    }
+
+    pub fn icon(self) -> &'static str {
+        match self {
+            Theme::System => "💻",
+            Theme::Light => "☀",
+            Theme::Dark => "🌙",
+        }
+    }
+
+    pub fn label(self, tr: &Strings) -> &'static str {
+        match self {
+            Theme::System => tr.theme_system,
+            Theme::Light => tr.theme_light,
+            Theme::Dark => tr.theme_dark,
+        }
+    }
+}
+
+/// Totals across every run of the app.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Stats {
+    pub total_clicks: u64,
+    /// Unpaused sending time, summed over all tabs.
+    pub total_runtime_secs: u64,
+    /// Unix time (seconds) sending was last on.
+    pub last_used: Option<i64>,
+}
+
+/// A sender tab besides the main one.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    #[serde(default)]
+    pub settings: Settings,
+}
+
+/// A named snapshot of the most commonly switched settings.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Preset {
+    pub name: String,
+    pub interval_ms: u64,
+    pub hold_ms: u64,
+    #[serde(default = "default_dest_ports")]
+    pub dest_ports: Vec<u16>,
+    pub address: String,
+}
+
+fn default_dest_ports() -> Vec<u16> {
+    Settings::default().dest_ports
+}
+
+impl Preset {
+    pub fn capture(name: &str, settings: &Settings) -> Self {
+        Self {
+            name: name.to_string(),
+            interval_ms: settings.interval_ms,
+            hold_ms: settings.hold_ms,
+            dest_ports: settings.dest_ports.clone(),
+            address: settings.address.clone(),
+        }
+    }
+
+    pub fn apply(&self, settings: &mut Settings) {
+        settings.interval_ms = self.interval_ms;
+        settings.hold_ms = self.hold_ms;
+        if !self.dest_ports.is_empty() {
+            settings.dest_ports = self.dest_ports.clone();
+        }
+        if is_valid_address(&self.address) {
+            settings.address = self.address.clone();
+        }
+    }
+}
+
+fn config_path() -> Option<PathBuf> {
+    ProjectDirs::from("", "", "osc-sender").map(|dirs| dirs.config_dir().join("config.json"))
+}
+
+/// Where eframe keeps the window position and size, next to the config.
+/// eframe restores them itself and moves the window back onto a connected
+/// monitor if it would open off-screen.
+pub fn window_state_path() -> Option<PathBuf> {
+    ProjectDirs::from("", "", "osc-sender").map(|dirs| dirs.config_dir().join("window.ron"))
+}
+
+pub fn load() -> Config {
+    let mut config: Config = config_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+    sanitize(&mut config);
+    config
+}
+
+pub fn save(config: &Config) -> io::Result<()> {
+    let path = config_path().ok_or_else(|| io::Error::other("no config directory"))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    write(config, &path)
+}
+
+/// Writes the config to a file of the user's choosing, for sharing.
+pub fn export(config: &Config, path: &Path) -> io::Result<()> {
+    write(config, path)
+}
+
+/// Reads an exported config. Unlike [`load`], an unreadable or invalid file
+/// is an error rather than the defaults, so nothing is applied from it.
+pub fn import(path: &Path) -> Result<Config, String> {
+    let json = fs::read_to_string(path).map_err(|e| format!("Failed to read config: {e}"))?;
+    let mut config: Config =
+        serde_json::from_str(&json).map_err(|e| format!("Invalid config: {e}"))?;
+    let profiles = config.profiles.iter_mut().map(|p| &mut p.settings);
+    for settings in std::iter::once(&mut config.settings).chain(profiles) {
+        // Unlike a config on disk, a bad import is refused rather than
+        // clamped, so it doesn't quietly run with other timings.
+        for (name, field, range) in ranged_fields() {
+            let value = *field(settings);
+            if !range.contains(&value) {
+                return Err(format!(
+                    "Invalid config: {name} {value} is outside {}..={}",
+                    range.start(),
+                    range.end()
+                ));
+            }
+        }
+        if !MULTICAST_TTL_RANGE.contains(&settings.multicast_ttl) {
+            return Err(format!(
+                "Invalid config: multicast_ttl {} is outside {}..={}",
+                settings.multicast_ttl,
+                MULTICAST_TTL_RANGE.start(),
+                MULTICAST_TTL_RANGE.end()
+            ));
+        }
+        if !is_valid_address(&settings.address) {
+            return Err(format!(
+                "Invalid config: bad address {:?}",
+                settings.address
+            ));
+        }
+        if settings.dest_ports.is_empty() {
+            return Err("Invalid config: no destination ports".to_string());
+        }
+    }
+    sanitize(&mut config);
+    Ok(config)
+}
+
+fn write(config: &Config, path: &Path) -> io::Result<()> {
+    let json = serde_json::to_string_pretty(config).map_err(io::Error::other)?;
+    fs::write(path, json)
+}
+
+/// Replaces values the app can't run with by their defaults.
+fn sanitize(config: &mut Config) {
+    sanitize_settings(&mut config.settings);
+    for profile in &mut config.profiles {
+        sanitize_settings(&mut profile.settings);
+    }
+    // Templates saved before the companion port could be set on its own.
+    config.quick_launcher_template = config
+        .quick_launcher_template
+        .replace("{port_plus_one}", "{listen_port}");
+    if !UI_SCALE_RANGE.contains(&config.ui_scale) {
+        config.ui_scale = 1.0;
+    }
+}
+
+/// A numeric setting the UI keeps within a range.
+type RangedField = (
+    &'static str,
+    fn(&mut Settings) -> &mut u64,
+    RangeInclusive<u64>,
+);
+
+/// Every millisecond setting with the range its slider allows. Values beyond
+/// these can overflow the sender's timing or leave it an empty range to pick
+/// from.
+fn ranged_fields() -> [RangedField; 13] {
+    [
+        ("interval_ms", |s| &mut s.interval_ms, INTERVAL_RANGE),
+        (
+            "interval_min_ms",
+            |s| &mut s.interval_min_ms,
+            INTERVAL_RANGE,
+        ),
+        (
+            "interval_max_ms",
+            |s| &mut s.interval_max_ms,
+            INTERVAL_RANGE,
+        ),
+        ("ramp_start_ms", |s| &mut s.ramp_start_ms, INTERVAL_RANGE),
+        ("ramp_end_ms", |s| &mut s.ramp_end_ms, INTERVAL_RANGE),
+        ("hold_ms", |s| &mut s.hold_ms, HOLD_RANGE),
+        ("hold_min_ms", |s| &mut s.hold_min_ms, HOLD_RANGE),
+        ("hold_max_ms", |s| &mut s.hold_max_ms, HOLD_RANGE),
+        (
+            "jitter_std_dev_ms",
+            |s| &mut s.jitter_std_dev_ms,
+            JITTER_STD_DEV_RANGE,
+        ),
+        (
+            "start_delay_ms",
+            |s| &mut s.start_delay_ms,
+            START_DELAY_RANGE,
+        ),
+        (
+            "burst_pause_ms",
+            |s| &mut s.burst_pause_ms,
+            BURST_PAUSE_RANGE,
+        ),
+        (
+            "sweep_period_ms",
+            |s| &mut s.sweep_period_ms,
+            SWEEP_PERIOD_RANGE,
+        ),
+        (
+            "heartbeat_interval_ms",
+            |s| &mut s.heartbeat_interval_ms,
+            HEARTBEAT_INTERVAL_RANGE,
+        ),
+    ]
+}
+
+fn sanitize_settings(settings: &mut Settings) {
+    let defaults = Settings::default();
+    if !is_valid_address(&settings.address) {
+        settings.address = defaults.address;
+    }
+    if settings.dest_ports.is_empty() {
+        settings.dest_ports = defaults.dest_ports;
+    }
+    for (_, field, range) in ranged_fields() {
+        let value = field(settings);
+        *value = (*value).clamp(*range.start(), *range.end());
+    }
+    settings.multicast_ttl = settings
+        .multicast_ttl
+        .clamp(*MULTICAST_TTL_RANGE.start(), *MULTICAST_TTL_RANGE.end());
+}
@@ -0,0 +1,134 @@
+//! System-wide hotkeys, so sending can be toggled while VRChat has focus.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+
+use global_hotkey::hotkey::HotKey;
+use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState};
+use osc_sender::osc_core::{AppState, Shared};
+
+pub const DEFAULT_TOGGLE_HOTKEY: &str = "Ctrl+Shift+O";
+pub const DEFAULT_STOP_HOTKEY: &str = "Ctrl+Shift+P";
+
+/// Owns the OS hotkey registration. Dropping it unregisters everything.
+pub struct Hotkeys {
+    manager: GlobalHotKeyManager,
+    toggle: Binding,
+    /// Emergency stop: halts sending and releases immediately.
+    stop: Binding,
+    /// Key-hold mode: sends while the key is down and releases when it
+    /// comes up, like auto-fire.
+    hold: Binding,
+    /// The profile tabs besides the main one. The toggle only switches the
+    /// main sender, but the emergency stop halts these too.
+    profiles: Arc<Mutex<Vec<Arc<Shared>>>>,
+}
+
+#[derive(Default)]
+struct Binding {
+    hotkey: Option<HotKey>,
+    /// Shared with the event handler, which matches events against it.
+    id: Arc<AtomicU32>,
+}
+
+impl Hotkeys {
+    pub fn new(shared: Arc<Shared>) -> Result<Self, String> {
+        let manager =
+            GlobalHotKeyManager::new().map_err(|e| format!("Global hotkeys unavailable: {e}"))?;
+
+        let (toggle, stop, hold) = (Binding::default(), Binding::default(), Binding::default());
+        let (toggle_id, stop_id, hold_id) = (toggle.id.clone(), stop.id.clone(), hold.id.clone());
+        let profiles = Arc::new(Mutex::new(Vec::<Arc<Shared>>::new()));
+        let handler_profiles = profiles.clone();
+        // The handler runs on the event loop thread, so it works even when
+        // the window is unfocused or not repainting.
+        GlobalHotKeyEvent::set_event_handler(Some(move |event: GlobalHotKeyEvent| {
+            let (id, pressed) = (event.id(), event.state() == HotKeyState::Pressed);
+            if id == hold_id.load(Ordering::SeqCst) {
+                shared.update(|s| {
+                    if pressed {
+                        s.request_start();
+                    } else if s.is_sending {
+                        s.set_sending(false);
+                    }
+                });
+            } else if pressed && id == toggle_id.load(Ordering::SeqCst) {
+                shared.update(AppState::toggle_sending);
+            } else if pressed && id == stop_id.load(Ordering::SeqCst) {
+                shared.emergency_stop();
+                for profile in handler_profiles.lock().unwrap().iter() {
+                    profile.emergency_stop();
+                }
+            }
+        }));
+
+        Ok(Self {
+            manager,
+            toggle,
+            stop,
+            hold,
+            profiles,
+        })
+    }
+
+    /// Replaces the profile senders the emergency stop also halts.
+    pub fn set_profiles(&self, profiles: Vec<Arc<Shared>>) {
+        *self.profiles.lock().unwrap() = profiles;
+    }
+
+    /// Replaces the toggle binding. On failure the previous binding is gone
+    /// and no toggle hotkey is registered.
+    pub fn set_toggle(&mut self, binding: &str) -> Result<(), String> {
+        self.toggle.set(&self.manager, binding)
+    }
+
+    /// Replaces the emergency stop binding, like [`Hotkeys::set_toggle`].
+    pub fn set_stop(&mut self, binding: &str) -> Result<(), String> {
+        self.stop.set(&self.manager, binding)
+    }
+
+    /// Replaces the key-hold binding; an empty binding turns key-hold mode
+    /// off.
+    pub fn set_hold(&mut self, binding: &str) -> Result<(), String> {
+        self.hold.set(&self.manager, binding)
+    }
+}
+
+impl Binding {
+    fn set(&mut self, manager: &GlobalHotKeyManager, binding: &str) -> Result<(), String> {
+        if let Some(old) = self.hotkey.take() {
+            let _ = manager.unregister(old);
+        }
+        if binding.is_empty() {
+            self.id.store(0, Ordering::SeqCst);
+            return Ok(());
+        }
+
+        let hotkey: HotKey = binding
+            .parse()
+            .map_err(|e| format!("Invalid hotkey \"{binding}\": {e}"))?;
+        manager
+            .register(hotkey)
+            .map_err(|e| format!("Failed to register hotkey \"{binding}\": {e}"))?;
+
+        self.id.store(hotkey.id(), Ordering::SeqCst);
+        self.hotkey = Some(hotkey);
+        Ok(())
+    }
+}
+
+impl Drop for Hotkeys {
+    fn drop(&mut self) {
+        for hotkey in [
+            self.toggle.hotkey.take(),
+            self.stop.hotkey.take(),
+            self.hold.hotkey.take(),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            let _ = self.manager.unregister(hotkey);
+        }
+        GlobalHotKeyEvent::set_event_handler(None::<fn(GlobalHotKeyEvent)>);
+    }
+}
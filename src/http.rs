@@ -0,0 +1,141 @@
+//! Optional HTTP control endpoint on localhost, so stream tools (OBS, Stream
+//! Deck, scripts) can start and stop sending without a global hotkey.
+
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use tiny_http::{Header, Method, Response, Server};
+
+use crate::osc_core::{AppState, BIND_RETRY_INTERVAL, Shared};
+
+/// How often a blocked `recv` wakes up to check for setting changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// The paths served, for listing in the UI. Every one answers with the
+/// sending state as JSON.
+pub const ENDPOINTS: [&str; 5] = ["/start", "/stop", "/toggle", "/click", "/status"];
+
+/// Runs until shutdown, serving whenever `http_enabled` is set and rebinding
+/// when the port changes.
+pub fn run_http(shared: &Shared) {
+    loop {
+        let port = {
+            let state = shared
+                .wake
+                .wait_while(shared.state.lock().unwrap(), |s| {
+                    !s.settings.http_enabled && !shared.is_shutdown()
+                })
+                .unwrap();
+            if shared.is_shutdown() {
+                return;
+            }
+            state.settings.http_port
+        };
+        let server = match Server::http((Ipv4Addr::LOCALHOST, port)) {
+            Ok(server) => {
+                shared.update(|s| s.http_error = None);
+                server
+            }
+            Err(e) => {
+                let mut state = shared.state.lock().unwrap();
+                state.http_error = Some(format!("Failed to serve HTTP on port {port}: {e}"));
+                let _ = shared
+                    .wake
+                    .wait_timeout_while(state, BIND_RETRY_INTERVAL, |s| serving_on(shared, s, port))
+                    .unwrap();
+                continue;
+            }
+        };
+
+        while serving_on(shared, &shared.state.lock().unwrap(), port) {
+            match server.recv_timeout(POLL_INTERVAL) {
+                Ok(Some(request)) => {
+                    let (status, body) = handle(shared, request.method(), request.url());
+                    let response = Response::from_string(body)
+                        .with_status_code(status)
+                        .with_header(
+                            Header::from_bytes("Content-Type", "application/json").unwrap(),
+                        );
+                    let _ = request.respond(response);
+                }
+                Ok(None) => {}
+                Err(e) => shared.update(|s| s.http_error = Some(format!("HTTP failed: {e}"))),
+            }
+        }
+    }
+}
+
+fn serving_on(shared: &Shared, state: &AppState, port: u16) -> bool {
+    state.settings.http_enabled && state.settings.http_port == port && !shared.is_shutdown()
+}
+
+/// Applies one request and returns the status code and JSON body. Only
+/// `/status` answers GET; everything that changes state takes POST, so a
+/// web page the browser merely loads can't start sending.
+fn handle(shared: &Shared, method: &Method, url: &str) -> (u16, String) {
+    let path = url.split('?').next().unwrap_or_default();
+    let allowed = match path {
+        "/status" => matches!(method, Method::Get | Method::Post),
+        _ => *method == Method::Post,
+    };
+    if !allowed && ENDPOINTS.contains(&path) {
+        return (405, r#"{"error":"method not allowed"}"#.to_string());
+    }
+    match path {
+        "/start" => shared.update(|s| {
+            s.request_start();
+        }),
+        "/stop" => shared.update(|s| {
+            if s.is_sending {
+                s.set_sending(false);
+            }
+        }),
+        "/toggle" => shared.update(AppState::toggle_sending),
+        "/click" => shared.request_click(),
+        "/status" => {}
+        _ => return (404, r#"{"error":"not found"}"#.to_string()),
+    }
+    let sending = shared.state.lock().unwrap().is_sending;
+    (200, format!(r#"{{"sending":{sending}}}"#))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::osc_core::Settings;
+
+    #[test]
+    fn endpoints_control_sending() {
+        let shared = Shared::new(Settings::default());
+        let sending = || shared.state.lock().unwrap().is_sending;
+
+        assert_eq!(
+            handle(&shared, &Method::Get, "/status"),
+            (200, r#"{"sending":false}"#.to_string())
+        );
+        assert_eq!(
+            handle(&shared, &Method::Post, "/start"),
+            (200, r#"{"sending":true}"#.to_string())
+        );
+        handle(&shared, &Method::Post, "/start");
+        assert!(sending());
+        handle(&shared, &Method::Post, "/toggle?from=deck");
+        assert!(!sending());
+        handle(&shared, &Method::Post, "/toggle");
+        handle(&shared, &Method::Post, "/stop");
+        assert!(!sending());
+
+        // Only the status can be read with GET.
+        for path in ["/start", "/toggle", "/click"] {
+            assert_eq!(handle(&shared, &Method::Get, path).0, 405, "{path}");
+        }
+        assert!(!sending());
+        handle(&shared, &Method::Post, "/start");
+        assert_eq!(handle(&shared, &Method::Get, "/stop").0, 405);
+        assert!(sending());
+        assert_eq!(handle(&shared, &Method::Post, "/status").0, 200);
+
+        assert_eq!(handle(&shared, &Method::Post, "/nope").0, 404);
+        assert_eq!(handle(&shared, &Method::Delete, "/start").0, 405);
+    }
+}
@@ -0,0 +1,947 @@
+//! UI text in each supported language.
+//!
+//! Every label lives in [`Strings`], so adding a field forces a translation
+//! for each language at compile time.
+
+use std::fs;
+
+use eframe::egui;
+use osc_sender::osc_core::{DestinationMode, InputMode, Jitter, Waveform};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    English,
+    Japanese,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::Japanese];
+
+    /// The language's own name, so it can be found without reading the
+    /// current one.
+    pub fn label(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Japanese => "日本語",
+        }
+    }
+
+    pub fn strings(self) -> &'static Strings {
+        match self {
+            Language::English => &EN,
+            Language::Japanese => &JA,
+        }
+    }
+}
+
+pub struct Strings {
+    pub always_on_top: &'static str,
+    pub language: &'static str,
+    pub theme_system: &'static str,
+    pub theme_light: &'static str,
+    pub theme_dark: &'static str,
+    pub theme_hover: fn(&str, &str) -> String,
+    pub ui_scale: &'static str,
+    pub preset: &'static str,
+    pub preset_none: &'static str,
+    pub delete_preset: &'static str,
+    pub preset_name_hint: &'static str,
+    pub save_preset: &'static str,
+    pub export_config: &'static str,
+    pub export_config_tip: &'static str,
+    pub import_config: &'static str,
+    pub import_config_tip: &'static str,
+    pub reset_defaults: &'static str,
+    pub reset_defaults_tip: &'static str,
+    pub confirm_reset_title: &'static str,
+    pub confirm_reset_body: &'static str,
+    pub reset: &'static str,
+    pub randomize_interval: &'static str,
+    pub jitter_name: fn(Jitter) -> &'static str,
+    pub ramp: &'static str,
+    pub ramp_tip: &'static str,
+    pub ramp_start: &'static str,
+    pub ramp_end: &'static str,
+    pub ramp_duration: &'static str,
+    pub ramp_current: fn(u64) -> String,
+    pub jitter_std_dev: &'static str,
+    pub jitter_std_dev_tip: &'static str,
+    pub min_interval: &'static str,
+    pub max_interval: &'static str,
+    pub show_hz: &'static str,
+    pub click_interval: &'static str,
+    pub click_rate: &'static str,
+    pub interval_tip: &'static str,
+    pub random_interval_tip: &'static str,
+    pub tap: &'static str,
+    pub tap_hint: &'static str,
+    pub bpm: &'static str,
+    pub hold_duration: &'static str,
+    pub hold_tip: &'static str,
+    pub randomize_hold: &'static str,
+    pub input_mode: &'static str,
+    pub input_mode_name: fn(InputMode) -> &'static str,
+    pub input_mode_tip: &'static str,
+    pub min_hold: &'static str,
+    pub max_hold: &'static str,
+    pub burst_mode: &'static str,
+    pub burst_count: &'static str,
+    pub burst_pause: &'static str,
+    pub axis_sweep: &'static str,
+    pub axis_sweep_tip: &'static str,
+    pub sweep_min: &'static str,
+    pub sweep_max: &'static str,
+    pub sweep_period: &'static str,
+    pub waveform: &'static str,
+    pub waveform_name: fn(Waveform) -> &'static str,
+    pub waveform_preview: &'static str,
+    pub main_tab: &'static str,
+    pub add_tab_tip: &'static str,
+    pub profile_name: fn(usize) -> String,
+    pub tab_name: &'static str,
+    pub remove_tab: &'static str,
+    pub text_mode: &'static str,
+    pub text_mode_tip: &'static str,
+    pub use_chatbox_address: &'static str,
+    pub text_hint: &'static str,
+    pub text_send_bool: &'static str,
+    pub text_send_bool_tip: &'static str,
+    pub max_packets_per_sec: &'static str,
+    pub max_packets_per_sec_tip: &'static str,
+    pub rate_limited: fn(u64) -> String,
+    pub hold_exceeds_interval: &'static str,
+    pub osc_address: &'static str,
+    pub release_address: &'static str,
+    pub release_address_hint: &'static str,
+    pub release_address_tip: &'static str,
+    pub arg_type: &'static str,
+    pub pressed: &'static str,
+    pub released: &'static str,
+    pub pressed_value: &'static str,
+    pub released_value: &'static str,
+    pub bundle_title: fn(usize) -> String,
+    pub bundle_hint: &'static str,
+    pub bundle_delay: &'static str,
+    pub bundle_delay_hover: &'static str,
+    pub add_message: &'static str,
+    pub cycle_title: fn(usize) -> String,
+    pub cycle_hint: &'static str,
+    pub cycle_random: &'static str,
+    pub cycle_random_tip: &'static str,
+    pub cycle_weight_tip: &'static str,
+    pub cycle_weights_zero: &'static str,
+    pub add_address: &'static str,
+    pub click_limit: &'static str,
+    pub no_limit_hover: &'static str,
+    pub clicks_sent: fn(u32) -> String,
+    pub clicks_sent_of: fn(u32, u32) -> String,
+    pub run_estimate: fn(u64, &str) -> String,
+    pub max_runtime: &'static str,
+    pub stops_in: fn(f32) -> String,
+    pub start_delay: &'static str,
+    pub auto_start: &'static str,
+    pub auto_start_tip: &'static str,
+    pub schedule: &'static str,
+    pub schedule_tip: &'static str,
+    pub schedule_waiting: fn(&str) -> String,
+    pub schedule_until: fn(&str) -> String,
+    pub send_osc: &'static str,
+    pub compact_mode: &'static str,
+    pub expand_layout: &'static str,
+    pub send_tip: &'static str,
+    pub confirm_send_title: &'static str,
+    pub confirm_send_body: fn(&str) -> String,
+    pub dont_ask_again: &'static str,
+    pub send_anyway: &'static str,
+    pub cancel: &'static str,
+    pub pause: &'static str,
+    pub resume: &'static str,
+    pub send_single_click: &'static str,
+    pub append_sequence: &'static str,
+    pub append_sequence_tip: &'static str,
+    pub dry_run: &'static str,
+    pub dry_run_tip: &'static str,
+    pub paused: &'static str,
+    pub paused_unfocused: &'static str,
+    pub paused_no_vrchat: &'static str,
+    pub paused_unresolved: &'static str,
+    pub send_failing: fn(u32) -> String,
+    pub send_rate: &'static str,
+    pub stats: &'static str,
+    pub total_clicks: fn(u64) -> String,
+    pub total_runtime: fn(&str) -> String,
+    pub last_used: fn(&str) -> String,
+    pub never_used: &'static str,
+    pub packets_per_sec: &'static str,
+    pub ping: &'static str,
+    pub http_control: &'static str,
+    pub http_control_tip: &'static str,
+    pub http_endpoint: fn(&str) -> &'static str,
+    pub ping_tip: fn(&str) -> String,
+    pub ping_needs_listen: &'static str,
+    pub ping_stats: fn(f64, f64, f64) -> String,
+    pub ping_waiting: &'static str,
+    pub advanced: &'static str,
+    pub send_buffer: &'static str,
+    pub send_buffer_tip: &'static str,
+    pub reuse_address: &'static str,
+    pub reuse_address_tip: &'static str,
+    pub send_retries: &'static str,
+    pub send_retries_tip: &'static str,
+    pub retry_stats: fn(u64, u64) -> String,
+    pub vrchat_running: &'static str,
+    pub vrchat_not_found: &'static str,
+    pub vrchat_checking: &'static str,
+    pub only_when_vrchat: &'static str,
+    pub only_when_vrchat_tip: &'static str,
+    pub pause_when_unfocused: &'static str,
+    pub pause_when_unfocused_tip: &'static str,
+    pub notify: &'static str,
+    pub notify_tip: &'static str,
+    pub starting_in: fn(f32) -> String,
+    pub destination_ip: &'static str,
+    pub destination_ip_tip: &'static str,
+    pub multicast: &'static str,
+    pub multicast_tip: &'static str,
+    pub not_multicast: &'static str,
+    pub multicast_ttl: &'static str,
+    pub multicast_ttl_tip: &'static str,
+    pub multicast_loop: &'static str,
+    pub multicast_loop_tip: &'static str,
+    pub broadcast: &'static str,
+    pub broadcast_tip: &'static str,
+    pub limited_broadcast_tip: &'static str,
+    pub confirm_broadcast_title: &'static str,
+    pub confirm_broadcast_body: &'static str,
+    pub enable_broadcast: &'static str,
+    pub destination_ports: &'static str,
+    pub send_from: &'static str,
+    pub send_from_tip: &'static str,
+    pub any_interface: &'static str,
+    pub refresh_interfaces: &'static str,
+    pub bound_to: fn(&str) -> String,
+    pub port_hint: &'static str,
+    pub add_port: &'static str,
+    pub add_port_tip: &'static str,
+    pub destination_mode_name: fn(DestinationMode) -> &'static str,
+    pub destination_mode_tip: &'static str,
+    pub port_down_tip: &'static str,
+    pub port_up_tip: &'static str,
+    pub port_remove_tip: &'static str,
+    pub detect_vrchat: &'static str,
+    pub detect_hover: &'static str,
+    pub found_vrchat: fn(u16) -> String,
+    pub listen_on_port: &'static str,
+    pub log_received: &'static str,
+    pub all_addresses_hint: &'static str,
+    pub heartbeat: &'static str,
+    pub heartbeat_tip: &'static str,
+    pub heartbeat_address: &'static str,
+    pub packets_sent: fn(u64, f32) -> String,
+    pub no_packets: &'static str,
+    #[cfg(feature = "tray")]
+    pub close_to_tray: &'static str,
+    #[cfg(feature = "tray")]
+    pub close_to_tray_tip: &'static str,
+    #[cfg(feature = "midi")]
+    pub midi_device: &'static str,
+    #[cfg(feature = "midi")]
+    pub midi_none: &'static str,
+    #[cfg(feature = "midi")]
+    pub midi_refresh: &'static str,
+    #[cfg(feature = "midi")]
+    pub midi_notes: &'static str,
+    #[cfg(feature = "midi")]
+    pub midi_any_note: &'static str,
+    #[cfg(feature = "midi")]
+    pub midi_notes_tip: &'static str,
+    #[cfg(feature = "midi")]
+    pub midi_note_off_release: &'static str,
+    #[cfg(feature = "midi")]
+    pub midi_note_off_release_tip: &'static str,
+    pub toggle_hotkey: &'static str,
+    pub apply: &'static str,
+    pub hotkey_hint: fn(&str) -> String,
+    pub stop_hotkey: &'static str,
+    pub stop_hotkey_hint: fn(&str) -> String,
+    pub hold_hotkey: &'static str,
+    pub hold_hotkey_hint: fn(&str) -> String,
+    pub capture_key: &'static str,
+    pub press_a_key: &'static str,
+    pub capture_key_tip: &'static str,
+    pub stopped_banner: &'static str,
+    pub quick_launcher: &'static str,
+    pub quick_launcher_tip: &'static str,
+    pub companion_port: &'static str,
+    pub companion_port_tip: &'static str,
+    pub template: &'static str,
+    pub template_hover: &'static str,
+    pub template_missing_port: &'static str,
+    pub copy: &'static str,
+    pub paste_launcher: &'static str,
+    pub paste_launcher_tip: &'static str,
+    pub launcher_invalid: &'static str,
+    pub script: &'static str,
+    pub script_hint: &'static str,
+    pub load_script: &'static str,
+    pub script_loaded: fn(&str, usize, f32) -> String,
+    pub unload_script: &'static str,
+    pub loop_script: &'static str,
+    pub record: &'static str,
+    pub stop_recording: &'static str,
+    pub record_tip: &'static str,
+    pub recorded_events: fn(usize) -> String,
+    pub recording: &'static str,
+    pub save_recording: &'static str,
+    pub replay: &'static str,
+    pub packet_bytes: &'static str,
+    pub log: &'static str,
+    pub clear: &'static str,
+    pub export_csv: &'static str,
+    pub exported: fn(usize) -> String,
+    pub export_failed: fn(&str) -> String,
+    pub status_idle: &'static str,
+    pub status_paused: fn(u64) -> String,
+    pub status_sending: fn(&str, &str, u64) -> String,
+}
+
+pub const EN: Strings = Strings {
+    always_on_top: "Always on top",
+    language: "Language",
+    theme_system: "Follow system theme",
+    theme_light: "Light theme",
+    theme_dark: "Dark theme",
+    theme_hover: |current, next| format!("{current} (click for {next})"),
+    ui_scale: "UI scale",
+    preset: "Preset:",
+    preset_none: "(none)",
+    delete_preset: "Delete preset",
+    preset_name_hint: "preset name",
+    save_preset: "Save current as preset",
+    export_config: "Export config...",
+    export_config_tip: "Save every setting, presets included, to a JSON file to share",
+    import_config: "Import config...",
+    import_config_tip: "Replace every setting with an exported JSON file. Nothing changes if the file is invalid.",
+    reset_defaults: "Reset to defaults",
+    reset_defaults_tip: "Put this tab's interval, hold, ports, address and modes back to their defaults. Hotkeys and presets are kept.",
+    confirm_reset_title: "Reset while sending?",
+    confirm_reset_body: "Sending is on. Reset every setting of this tab to its default? Sending continues with the defaults.",
+    reset: "Reset",
+    randomize_interval: "Randomize interval",
+    jitter_name: |jitter| match jitter {
+        Jitter::Uniform => "Uniform range",
+        Jitter::Gaussian => "Gaussian jitter",
+    },
+    jitter_std_dev: "Standard deviation (ms)",
+    ramp: "Ramp interval",
+    ramp_tip: "Move the click interval from the start value to the end value over the ramp duration after sending begins, then hold it at the end value. Replaces the click interval above; a uniform random range ignores it.",
+    ramp_start: "Start interval (ms)",
+    ramp_end: "End interval (ms)",
+    ramp_duration: "Ramp duration (s):",
+    ramp_current: |ms| format!("Current interval: {ms}ms"),
+    jitter_std_dev_tip: "How far intervals spread around the click interval. About two thirds fall within one standard deviation, and none beyond three.",
+    min_interval: "Min interval (ms)",
+    max_interval: "Max interval (ms)",
+    show_hz: "Show interval as frequency (Hz)",
+    click_interval: "Click interval (ms)",
+    click_rate: "Click rate (Hz)",
+    interval_tip: "Time from the start of one click to the start of the next, \
+        including the hold. 1000 ms is the default.",
+    random_interval_tip: "Each click waits a random time between the minimum and maximum",
+    tap: "Tap",
+    tap_hint: "Tap in time to set the interval",
+    bpm: "BPM:",
+    hold_duration: "Hold duration (ms)",
+    hold_tip: "How long the button stays pressed before it is released. \
+        200 ms or more is recommended to reliably press Unity UI buttons.",
+    randomize_hold: "Randomize hold",
+    input_mode: "Input:",
+    input_mode_name: |mode| match mode {
+        InputMode::Momentary => "Momentary",
+        InputMode::Toggle => "Toggle",
+    },
+    input_mode_tip: "Momentary presses and releases after the hold time on every click. Toggle flips between on and off on every click and leaves the last state when sending stops.",
+    min_hold: "Min hold (ms)",
+    max_hold: "Max hold (ms)",
+    burst_mode: "Burst mode",
+    burst_count: "Clicks per burst",
+    burst_pause: "Pause between bursts (ms)",
+    max_packets_per_sec: "Max packets/s",
+    max_packets_per_sec_tip: "Clicks are slowed down so no more than this many packets are sent per second, counting press, release and every port. 0 means no cap.",
+    rate_limited: |ms| format!("⚠ Rate capped: one click every {ms} ms at most"),
+    hold_exceeds_interval: "⚠ The hold is as long as the interval, so the input barely gets released between clicks. Increase the interval or decrease the hold.",
+    axis_sweep: "Axis sweep",
+    axis_sweep_tip: "Send a float on the address every interval instead of clicking, moving between the two bounds. Hold and burst settings are ignored.",
+    sweep_min: "From",
+    sweep_max: "to",
+    sweep_period: "Period (ms)",
+    waveform: "Waveform:",
+    waveform_name: |waveform| match waveform {
+        Waveform::Triangle => "Triangle",
+        Waveform::Ramp => "Ramp",
+        Waveform::Sine => "Sine",
+        Waveform::Square => "Square",
+    },
+    waveform_preview: "Sent value",
+    main_tab: "Main",
+    add_tab_tip: "Add a sender tab with a copy of this tab's settings. Every tab sends on its own, whichever is selected.",
+    profile_name: |n| format!("Profile {n}"),
+    tab_name: "Name",
+    remove_tab: "Remove tab",
+    text_mode: "Text",
+    text_mode_tip: "Send the text below as a string on the address every interval instead of clicking, e.g. to VRChat's chatbox. Hold and burst settings are ignored.",
+    use_chatbox_address: "Use /chatbox/input",
+    text_hint: "Message",
+    text_send_bool: "Append bool",
+    text_send_bool_tip: "Follow the string with a bool. The chatbox shows the text immediately when it is true and opens the keyboard when it is false.",
+    osc_address: "OSC Address:",
+    release_address: "Release address:",
+    release_address_hint: "Same as press",
+    release_address_tip: "Send releases (the off value) here instead, for paired set/reset addresses",
+    arg_type: "Argument type:",
+    pressed: "Pressed:",
+    released: "Released:",
+    pressed_value: "Pressed value",
+    released_value: "Released value",
+    bundle_title: |count| format!("Bundle ({count} extra)"),
+    bundle_hint: "Sent in the same bundle as the main address",
+    bundle_delay: "Bundle delay (ms):",
+    bundle_delay_hover: "Timetag bundles this far ahead; 0 = immediately",
+    add_message: "Add message",
+    cycle_title: |count| format!("Address cycle ({count})"),
+    cycle_hint: "Each click goes to the next address in turn. Empty uses the main address.",
+    cycle_random: "Random by weight",
+    cycle_random_tip: "Each click picks one address at random, more often the higher its weight",
+    cycle_weight_tip: "Weight: relative chance of this address being picked",
+    cycle_weights_zero: "Every weight is 0: give at least one address a weight above 0. Clicks cycle in turn until then.",
+    add_address: "Add address",
+    click_limit: "Click limit:",
+    no_limit_hover: "0 = no limit",
+    clicks_sent: |sent| format!("{sent} sent"),
+    clicks_sent_of: |sent, limit| format!("{sent} / {limit} sent"),
+    run_estimate: |packets, bytes| format!("The whole run is about {packets} packets, {bytes}"),
+    max_runtime: "Max runtime (s):",
+    stops_in: |secs| format!("stops in {secs}s"),
+    start_delay: "Start delay (ms)",
+    auto_start: "Start sending on launch",
+    auto_start_tip: "Begins sending as soon as the app opens, after the start delay",
+    schedule: "Schedule",
+    schedule_tip: "Turn sending on when the clock reaches the first time and off at the second, every day. A start later than the stop runs past midnight.",
+    schedule_waiting: |time| format!("Scheduled, waiting until {time}"),
+    schedule_until: |time| format!("Scheduled window, stops at {time}"),
+    send_osc: "Send OSC",
+    compact_mode: "Compact mode: show only the send toggle",
+    expand_layout: "Show every setting again",
+    send_tip: "Keep clicking while checked. Unchecking always sends a final release.",
+    confirm_send_title: "Send to another machine?",
+    confirm_send_body: |ip| format!("{ip} is not this computer. Start sending OSC to it?"),
+    dont_ask_again: "Don't ask again for this address",
+    send_anyway: "Send",
+    cancel: "Cancel",
+    pause: "Pause",
+    resume: "Resume",
+    send_single_click: "Send single click",
+    append_sequence: "Append sequence number",
+    append_sequence_tip: "Add an increasing Int as an extra argument to every message, so the receiver can detect dropped packets. Restarts from 0 each time sending is turned on.",
+    dry_run: "Dry run",
+    dry_run_tip: "Log and count packets without sending them",
+    paused: "paused",
+    paused_unfocused: "paused (window unfocused)",
+    paused_no_vrchat: "paused (VRChat not running)",
+    paused_unresolved: "paused (hostname not resolved)",
+    send_failing: |count| format!("{count} sends failed in a row, rebinding the socket"),
+    send_rate: "Send rate",
+    stats: "Stats",
+    total_clicks: |clicks| format!("Clicks sent in total: {clicks}"),
+    total_runtime: |time| format!("Total sending time: {time}"),
+    last_used: |at| format!("Last used: {at}"),
+    never_used: "Not used yet",
+    packets_per_sec: "Packets/s",
+    ping: "Measure latency",
+    http_control: "HTTP control on port",
+    http_control_tip: "Serve control endpoints on this computer only, for stream tools such as OBS or Stream Deck. Every endpoint takes POST (/status also GET) and answers with {\"sending\": true|false}.",
+    http_endpoint: |endpoint| match endpoint {
+        "/start" => "start sending",
+        "/stop" => "stop sending",
+        "/toggle" => "toggle sending",
+        "/click" => "send one click",
+        _ => "sending state only",
+    },
+    ping_tip: |address| {
+        format!(
+            "Send a numbered {address} message every second and time its echo back to the listen port. Needs something at the destination that echoes it."
+        )
+    },
+    ping_needs_listen: "Turn on listening to receive the echoes",
+    ping_stats: |min, avg, max| {
+        format!("Round trip: min {min:.2} / avg {avg:.2} / max {max:.2} ms")
+    },
+    ping_waiting: "Waiting for an echo...",
+    advanced: "Advanced",
+    send_buffer: "Send buffer (bytes):",
+    send_buffer_tip: "SO_SNDBUF of the send socket. 0 keeps the OS default.",
+    reuse_address: "Reuse address (SO_REUSEADDR)",
+    reuse_address_tip: "Allow binding the send socket to an address another socket is using",
+    send_retries: "Retries:",
+    send_retries_tip: "Retry a failed send this many times, waiting 5 ms, then 10 ms, ... before each retry. A press is retried before its release is sent.",
+    retry_stats: |retries, failed| format!("{retries} retried, {failed} failed"),
+    vrchat_running: "VRChat: running",
+    vrchat_not_found: "VRChat: not found",
+    vrchat_checking: "VRChat: checking...",
+    only_when_vrchat: "Only send while VRChat is running",
+    only_when_vrchat_tip: "Pause sending while no VRChat process is found and resume once it starts",
+    pause_when_unfocused: "Pause when unfocused",
+    pause_when_unfocused_tip: "Pause sending while another app has focus and resume when this window is focused again. A manual pause stays paused.",
+    notify: "Notify on start and stop",
+    notify_tip: "Show a desktop notification when sending starts, and when the click limit, the max runtime, the schedule or a script pass stops it.",
+    starting_in: |secs| format!("starting in {secs}…"),
+    destination_ip: "Destination IP:",
+    destination_ip_tip: "An IP address, or a hostname such as my-gaming-pc.local that is looked up again every 30 s",
+    multicast: "Multicast",
+    multicast_tip: "Send to a multicast group (224.0.0.0/4) that several listeners join",
+    not_multicast: "Not a multicast address: enter one in 224.0.0.0 – 239.255.255.255",
+    multicast_ttl: "TTL:",
+    multicast_ttl_tip: "How many routers a packet may cross; 1 stays on the local network",
+    multicast_loop: "Loopback",
+    multicast_loop_tip: "Also deliver to listeners on this PC",
+    broadcast: "Broadcast",
+    broadcast_tip: "Allow sending to 255.255.255.255 or a subnet broadcast address like 192.168.1.255",
+    limited_broadcast_tip: "Send to every device on the local network",
+    confirm_broadcast_title: "Enable broadcast?",
+    confirm_broadcast_body: "Broadcast packets reach every device on the network, which is noisy for everyone on it. Enable broadcast until the app is closed?",
+    enable_broadcast: "Enable",
+    destination_ports: "Destination Ports:",
+    send_from: "Send from:",
+    send_from_tip: "Local address the packets are sent from. Pick one when a VPN or another network adapter sends OSC out the wrong way.",
+    any_interface: "Any interface",
+    refresh_interfaces: "Refresh the address list",
+    bound_to: |addr| format!("Sending from {addr}"),
+    port_hint: "port",
+    add_port: "Add port",
+    add_port_tip: "Also send every click to another port, e.g. a second VRChat instance",
+    destination_mode_name: |mode| match mode {
+        DestinationMode::Broadcast => "Send to all",
+        DestinationMode::RoundRobin => "Round-robin",
+    },
+    destination_mode_tip: "Send each click to every port, or to one port after another",
+    port_down_tip: "Previous port (−2)",
+    port_up_tip: "Next port (+2). Each instance uses two ports, so steps are 2.",
+    port_remove_tip: "Stop sending to this port",
+    detect_vrchat: "Detect VRChat",
+    detect_hover: "Find VRChat's OSC port via OSCQuery",
+    found_vrchat: |port| format!("Found VRChat on port {port}"),
+    listen_on_port: "Listen on port",
+    log_received: "Log received:",
+    all_addresses_hint: "all addresses",
+    heartbeat: "Heartbeat every",
+    heartbeat_tip: "Keep sending true to a separate address at its own interval, even while clicking is off or paused, for receivers that time out when nothing arrives.",
+    heartbeat_address: "Heartbeat address:",
+    packets_sent: |count, secs| format!("Sent {count} packets, last {secs:.1}s ago"),
+    no_packets: "No packets sent yet",
+    #[cfg(feature = "tray")]
+    close_to_tray: "Close to tray",
+    #[cfg(feature = "tray")]
+    close_to_tray_tip: "Closing the window hides it to the tray and keeps sending. Use Quit in the tray menu to exit.",
+    #[cfg(feature = "midi")]
+    midi_device: "Device:",
+    #[cfg(feature = "midi")]
+    midi_none: "None",
+    #[cfg(feature = "midi")]
+    midi_refresh: "Refresh the device list",
+    #[cfg(feature = "midi")]
+    midi_notes: "Notes:",
+    #[cfg(feature = "midi")]
+    midi_any_note: "any",
+    #[cfg(feature = "midi")]
+    midi_notes_tip: "MIDI note numbers that click, separated by commas (middle C is 60). Leave empty to click on every note.",
+    #[cfg(feature = "midi")]
+    midi_note_off_release: "Release on Note-Off",
+    #[cfg(feature = "midi")]
+    midi_note_off_release_tip: "A Note-On only presses and the Note-Off releases, so the input is held as long as the key. Otherwise every Note-On is a whole click.",
+    toggle_hotkey: "Toggle hotkey:",
+    apply: "Apply",
+    hotkey_hint: |hotkey| format!("Press {hotkey} anywhere to toggle sending"),
+    stop_hotkey: "Emergency stop:",
+    stop_hotkey_hint: |hotkey| format!("Press {hotkey} anywhere to stop and release at once"),
+    hold_hotkey: "Send while held:",
+    hold_hotkey_hint: |hotkey| format!("Hold {hotkey} anywhere to send, release it to stop"),
+    capture_key: "Capture key",
+    press_a_key: "Press a key… (Esc cancels)",
+    capture_key_tip: "Fill in the send-while-held key by pressing it in this window, then apply. Leave the field empty to turn key-hold mode off. A bare key is taken from every other app while bound.",
+    stopped_banner: "STOPPED",
+    quick_launcher: "Quick Launcher OSC setting value",
+    quick_launcher_tip: "Paste this into the OSC field of VRChat Quick Launcher. \
+        The format is port:localhost:port: VRChat receives on the first port \
+        and sends its own OSC output to localhost on the companion port.",
+    companion_port: "Companion port:",
+    companion_port_tip: "The port VRChat sends its OSC output to, and this app listens on. Independent of the send port.",
+    template: "Template:",
+    template_hover: "Placeholders: {port}, {listen_port}",
+    template_missing_port: "Template must contain {port}",
+    copy: "Copy",
+    paste_launcher: "Paste:",
+    paste_launcher_tip: "Paste a Quick Launcher value to send to its first port and listen on its last",
+    launcher_invalid: "Expected port:host:port, e.g. 9000:localhost:9001",
+    script: "Script",
+    script_hint: "While a script is loaded, sending plays it instead of clicking. Format: [{\"delay_ms\": 200, \"address\": \"/input/Jump\", \"value\": 1, \"type\": \"Int\"}, ...]",
+    load_script: "Load script…",
+    script_loaded: |name, count, secs| format!("{name}: {count} events, {secs:.1}s"),
+    unload_script: "Unload the script and go back to clicking",
+    loop_script: "Loop the script",
+    record: "⏺ Record",
+    stop_recording: "⏹ Stop recording",
+    record_tip: "Capture everything sent, with its timing, so it can be saved as a script or replayed.",
+    recorded_events: |count| format!("{count} events recorded"),
+    recording: "Recording",
+    save_recording: "Save recording…",
+    replay: "Replay",
+    packet_bytes: "Packet bytes",
+    log: "Log",
+    clear: "Clear",
+    export_csv: "Export CSV",
+    exported: |count| format!("Exported {count} entries"),
+    export_failed: |error| format!("Failed to export log: {error}"),
+    status_idle: "Idle",
+    status_paused: |packets| format!("Paused · {packets} packets"),
+    status_sending: |dests, interval, packets| {
+        format!("Sending to {dests} every {interval} · {packets} packets")
+    },
+};
+
+pub const JA: Strings = Strings {
+    always_on_top: "常に最前面",
+    language: "言語",
+    theme_system: "システム設定に従う",
+    theme_light: "ライトテーマ",
+    theme_dark: "ダークテーマ",
+    theme_hover: |current, next| format!("{current}（クリックで{next}）"),
+    ui_scale: "UIの拡大率",
+    preset: "プリセット:",
+    preset_none: "（なし）",
+    delete_preset: "プリセットを削除",
+    preset_name_hint: "プリセット名",
+    save_preset: "現在の設定をプリセットとして保存",
+    export_config: "設定をエクスポート...",
+    export_config_tip: "プリセットを含むすべての設定をJSONファイルに保存して共有できます",
+    import_config: "設定をインポート...",
+    import_config_tip: "エクスポートしたJSONファイルですべての設定を置き換えます。ファイルが不正な場合は何も変更しません。",
+    reset_defaults: "初期設定に戻す",
+    reset_defaults_tip: "このタブの間隔・ホールド時間・ポート・アドレス・モードを初期値に戻します。ホットキーとプリセットはそのままです。",
+    confirm_reset_title: "送信中にリセットしますか？",
+    confirm_reset_body: "送信中です。このタブのすべての設定を初期値に戻しますか？送信は初期設定のまま続きます。",
+    reset: "リセット",
+    randomize_interval: "クリック間隔をランダムにする",
+    jitter_name: |jitter| match jitter {
+        Jitter::Uniform => "一様な範囲",
+        Jitter::Gaussian => "ガウス分布",
+    },
+    jitter_std_dev: "標準偏差 (ms)",
+    ramp: "間隔を徐々に変化",
+    ramp_tip: "送信開始後、変化時間をかけてクリック間隔を開始値から終了値へ直線的に変化させ、その後は終了値を保ちます。上のクリック間隔の代わりに使われます（一様な範囲のランダム間隔では無視されます）。",
+    ramp_start: "開始時の間隔 (ms)",
+    ramp_end: "終了時の間隔 (ms)",
+    ramp_duration: "変化時間 (秒):",
+    ramp_current: |ms| format!("現在の間隔: {ms}ms"),
+    jitter_std_dev_tip: "クリック間隔を中心としたばらつきの大きさです。約3分の2が標準偏差1つ分の範囲に収まり、3つ分を超えることはありません。",
+    min_interval: "最小間隔 (ms)",
+    max_interval: "最大間隔 (ms)",
+    show_hz: "クリック間隔を周波数 (Hz) で表示",
+    click_interval: "クリック間隔 (ms)",
+    click_rate: "クリック頻度 (Hz)",
+    interval_tip: "クリックの開始から次のクリックの開始までの時間です（ホールド時間を含む）。\
+        デフォルトは1000msです。",
+    random_interval_tip: "クリックごとに最小〜最大の間でランダムな時間待ちます",
+    tap: "タップ",
+    tap_hint: "リズムに合わせてタップすると間隔を設定",
+    bpm: "BPM:",
+    hold_duration: "ホールド時間 (ms)",
+    hold_tip: "ボタンを押してから離すまでの時間です。\
+        Unity UIボタンを確実に押すには200ms以上を推奨します。",
+    randomize_hold: "ホールド時間をランダムにする",
+    input_mode: "入力:",
+    input_mode_name: |mode| match mode {
+        InputMode::Momentary => "モーメンタリ",
+        InputMode::Toggle => "トグル",
+    },
+    input_mode_tip: "モーメンタリはクリックごとに押してホールド時間後に離します。トグルはクリックごとにON/OFFを切り替え、送信を止めても最後の状態のままにします。",
+    min_hold: "最小ホールド (ms)",
+    max_hold: "最大ホールド (ms)",
+    burst_mode: "バーストモード",
+    burst_count: "バーストごとのクリック数",
+    burst_pause: "バースト間の休止 (ms)",
+    max_packets_per_sec: "最大パケット数/秒",
+    max_packets_per_sec_tip: "1秒あたりの送信パケット数（押下・解放・すべてのポートを含む）がこの値を超えないよう、クリックの間隔を広げます。0で無制限。",
+    rate_limited: |ms| format!("⚠ 送信レートを制限中: クリック間隔は最短{ms}ms"),
+    hold_exceeds_interval: "⚠ ホールド時間がクリック間隔以上のため、クリックの間にほとんど離されません。間隔を長くするか、ホールド時間を短くしてください。",
+    axis_sweep: "軸スイープ",
+    axis_sweep_tip: "クリックの代わりに、2つの値の間を変化するfloat値を間隔ごとにアドレスへ送信します。押下時間とバーストの設定は無視されます。",
+    sweep_min: "範囲",
+    sweep_max: "～",
+    sweep_period: "周期 (ms)",
+    waveform: "波形:",
+    waveform_name: |waveform| match waveform {
+        Waveform::Triangle => "三角波",
+        Waveform::Ramp => "ランプ",
+        Waveform::Sine => "サイン波",
+        Waveform::Square => "矩形波",
+    },
+    waveform_preview: "送信値",
+    main_tab: "メイン",
+    add_tab_tip: "このタブの設定をコピーして送信タブを追加します。各タブは選択中かどうかに関わらず個別に送信します。",
+    profile_name: |n| format!("プロファイル{n}"),
+    tab_name: "名前",
+    remove_tab: "タブを削除",
+    text_mode: "テキスト",
+    text_mode_tip: "クリックの代わりに、下のテキストを文字列として間隔ごとにアドレスへ送信します（VRChatのチャットボックスなど）。押下時間とバーストの設定は無視されます。",
+    use_chatbox_address: "/chatbox/input を使用",
+    text_hint: "メッセージ",
+    text_send_bool: "boolを追加",
+    text_send_bool_tip: "文字列の後にboolを送信します。チャットボックスはtrueならすぐに表示し、falseならキーボードを開きます。",
+    osc_address: "OSCアドレス:",
+    release_address: "リリース先アドレス:",
+    release_address_hint: "押下と同じ",
+    release_address_tip: "リリース（離した時の値）をこのアドレスに送信します。セット／リセットが別アドレスの場合向け",
+    arg_type: "引数の型:",
+    pressed: "押下時:",
+    released: "離した時:",
+    pressed_value: "押下時の値",
+    released_value: "離した時の値",
+    bundle_title: |count| format!("バンドル（追加{count}件）"),
+    bundle_hint: "メインのアドレスと同じバンドルで送信されます",
+    bundle_delay: "バンドルの遅延 (ms):",
+    bundle_delay_hover: "この時間だけ先のタイムタグを付けます（0 = 即時）",
+    add_message: "メッセージを追加",
+    cycle_title: |count| format!("アドレス巡回（{count}件）"),
+    cycle_hint: "クリックごとに次のアドレスへ順番に送信します。空の場合はメインのアドレスを使います。",
+    cycle_random: "重み付きランダム",
+    cycle_random_tip: "クリックごとにアドレスをランダムに1つ選びます。重みが大きいほど選ばれやすくなります",
+    cycle_weight_tip: "重み: このアドレスが選ばれる相対的な確率",
+    cycle_weights_zero: "すべての重みが0です。少なくとも1つのアドレスの重みを0より大きくしてください（それまでは順番に送信します）。",
+    add_address: "アドレスを追加",
+    click_limit: "クリック回数の上限:",
+    no_limit_hover: "0 = 無制限",
+    clicks_sent: |sent| format!("{sent}回送信"),
+    clicks_sent_of: |sent, limit| format!("{sent} / {limit}回送信"),
+    run_estimate: |packets, bytes| format!("全体で約{packets}パケット、{bytes}"),
+    max_runtime: "最大実行時間 (秒):",
+    stops_in: |secs| format!("あと{secs}秒で停止"),
+    start_delay: "開始までの待ち時間 (ms)",
+    auto_start: "起動時に送信を開始",
+    auto_start_tip: "アプリを開くとすぐに（開始までの待ち時間の後）送信を始めます",
+    schedule: "スケジュール",
+    schedule_tip: "毎日、最初の時刻になると送信をONにし、2つ目の時刻でOFFにします。開始が終了より遅い場合は日付をまたぎます。",
+    schedule_waiting: |time| format!("スケジュール待機中（{time}に開始）"),
+    schedule_until: |time| format!("スケジュール実行中（{time}に停止）"),
+    send_osc: "OSCを送信",
+    compact_mode: "コンパクト表示: 送信のON/OFFだけを表示",
+    expand_layout: "すべての設定を再び表示",
+    send_tip: "チェック中はクリックを送り続けます。外すと必ず最後に離す信号を送ります。",
+    confirm_send_title: "別のマシンに送信しますか？",
+    confirm_send_body: |ip| {
+        format!("{ip} はこのコンピューターではありません。OSCの送信を開始しますか？")
+    },
+    dont_ask_again: "このアドレスでは今後確認しない",
+    send_anyway: "送信",
+    cancel: "キャンセル",
+    pause: "一時停止",
+    resume: "再開",
+    send_single_click: "1回だけクリック",
+    append_sequence: "シーケンス番号を付加",
+    append_sequence_tip: "すべてのメッセージに増加していくInt値を追加の引数として付けます。受信側でパケットの欠落を検出できます。送信をONにするたびに0から数え直します。",
+    dry_run: "ドライラン",
+    dry_run_tip: "実際には送信せず、ログとカウントだけ行います",
+    paused: "一時停止中",
+    paused_unfocused: "一時停止中（ウィンドウ非アクティブ）",
+    paused_no_vrchat: "一時停止中（VRChat未起動）",
+    paused_unresolved: "一時停止中（ホスト名を解決できません）",
+    send_failing: |count| format!("{count}回連続で送信に失敗しました。ソケットを再作成します"),
+    send_rate: "送信レート",
+    stats: "統計",
+    total_clicks: |clicks| format!("累計クリック数: {clicks}"),
+    total_runtime: |time| format!("累計送信時間: {time}"),
+    last_used: |at| format!("最終使用: {at}"),
+    never_used: "まだ使用されていません",
+    packets_per_sec: "パケット/秒",
+    ping: "遅延を測定",
+    http_control: "HTTP制御 ポート",
+    http_control_tip: "OBSやStream Deckなどの配信ツール向けに、このコンピューター内だけで制御用のエンドポイントを提供します。すべてのエンドポイントはPOSTで（/statusはGETでも）使え、{\"sending\": true|false}を返します。",
+    http_endpoint: |endpoint| match endpoint {
+        "/start" => "送信を開始",
+        "/stop" => "送信を停止",
+        "/toggle" => "送信を切り替え",
+        "/click" => "1回クリック",
+        _ => "送信状態のみ",
+    },
+    ping_tip: |address| {
+        format!(
+            "番号付きの{address}メッセージを毎秒送り、受信ポートに返ってくるまでの時間を測ります。送信先でメッセージを折り返すエコーが必要です。"
+        )
+    },
+    ping_needs_listen: "エコーを受け取るには受信をONにしてください",
+    ping_stats: |min, avg, max| {
+        format!("往復時間: 最小 {min:.2} / 平均 {avg:.2} / 最大 {max:.2} ms")
+    },
+    ping_waiting: "エコーを待っています...",
+    advanced: "詳細設定",
+    send_buffer: "送信バッファ (バイト):",
+    send_buffer_tip: "送信ソケットのSO_SNDBUF。0でOSの既定値を使います。",
+    reuse_address: "アドレスを再利用 (SO_REUSEADDR)",
+    reuse_address_tip: "他のソケットが使用中のアドレスにも送信ソケットをバインドできるようにします",
+    send_retries: "再送回数:",
+    send_retries_tip: "送信に失敗したとき、5ms、10ms…と待ちながらこの回数まで再送します。押す信号は離す信号の前に再送されます。",
+    retry_stats: |retries, failed| format!("再送 {retries}回 / 失敗 {failed}回"),
+    vrchat_running: "VRChat: 起動中",
+    vrchat_not_found: "VRChat: 見つかりません",
+    vrchat_checking: "VRChat: 確認中...",
+    only_when_vrchat: "VRChat起動中のみ送信",
+    only_when_vrchat_tip: "VRChatのプロセスが見つからない間は送信を一時停止し、起動すると再開します",
+    pause_when_unfocused: "非アクティブ時に一時停止",
+    pause_when_unfocused_tip: "他のアプリにフォーカスがある間は送信を一時停止し、このウィンドウに戻ると再開します。手動の一時停止は解除されません。",
+    notify: "開始・停止を通知",
+    notify_tip: "送信の開始時と、クリック上限・最大実行時間・スケジュール・スクリプトの終了で停止したときにデスクトップ通知を表示します。",
+    starting_in: |secs| format!("{secs}秒後に開始…"),
+    destination_ip: "送信先IP:",
+    destination_ip_tip: "IPアドレス、またはmy-gaming-pc.localのようなホスト名（30秒ごとに再解決）",
+    multicast: "マルチキャスト",
+    multicast_tip: "複数の受信側が参加するマルチキャストグループ (224.0.0.0/4) に送信します",
+    not_multicast: "マルチキャストアドレスではありません: 224.0.0.0〜239.255.255.255を入力してください",
+    multicast_ttl: "TTL:",
+    multicast_ttl_tip: "パケットが越えられるルーターの数です。1ならローカルネットワーク内に留まります",
+    multicast_loop: "ループバック",
+    multicast_loop_tip: "このPC上の受信側にも届けます",
+    broadcast: "ブロードキャスト",
+    broadcast_tip: "255.255.255.255や192.168.1.255のようなサブネットのブロードキャストアドレスへの送信を許可します",
+    limited_broadcast_tip: "ローカルネットワーク上のすべての機器に送信します",
+    confirm_broadcast_title: "ブロードキャストを有効にしますか？",
+    confirm_broadcast_body: "ブロードキャストのパケットはネットワーク上のすべての機器に届くため、他の機器の負担になります。アプリを閉じるまでブロードキャストを有効にしますか？",
+    enable_broadcast: "有効にする",
+    destination_ports: "送信先ポート:",
+    send_from: "送信元:",
+    send_from_tip: "パケットを送信するローカルアドレスです。VPNや複数のネットワークアダプターがあり、OSCが意図しない経路で送られる場合に指定します。",
+    any_interface: "自動",
+    refresh_interfaces: "アドレス一覧を更新",
+    bound_to: |addr| format!("送信元ソケット: {addr}"),
+    port_hint: "ポート",
+    add_port: "ポートを追加",
+    add_port_tip: "別のポートにも同時に送信します（2つ目のVRChatなど）",
+    destination_mode_name: |mode| match mode {
+        DestinationMode::Broadcast => "全てに送信",
+        DestinationMode::RoundRobin => "順番に送信",
+    },
+    destination_mode_tip: "クリックごとに全ポートへ送るか、1ポートずつ順番に送るかを選びます",
+    port_down_tip: "前のポート（−2）",
+    port_up_tip: "次のポート（+2）。1つの起動につき2つのポートを使うため2ずつ変わります。",
+    port_remove_tip: "このポートへの送信をやめます",
+    detect_vrchat: "VRChatを検出",
+    detect_hover: "OSCQueryでVRChatのOSCポートを探します",
+    found_vrchat: |port| format!("VRChatをポート{port}で検出しました"),
+    listen_on_port: "受信ポート",
+    log_received: "受信ログの対象:",
+    all_addresses_hint: "すべてのアドレス",
+    heartbeat: "ハートビート間隔",
+    heartbeat_tip: "クリックの送信がOFFや一時停止中でも、別のアドレスへ独自の間隔でtrueを送り続けます。何も届かないと切断する受信側向けです。",
+    heartbeat_address: "ハートビートのアドレス:",
+    packets_sent: |count, secs| format!("{count}パケット送信済み、最後の送信は{secs:.1}秒前"),
+    no_packets: "まだ送信していません",
+    #[cfg(feature = "tray")]
+    close_to_tray: "閉じるとタスクトレイに格納",
+    #[cfg(feature = "tray")]
+    close_to_tray_tip: "ウィンドウを閉じてもタスクトレイに隠れるだけで、送信は続きます。終了するにはトレイメニューの「Quit」を使います。",
+    #[cfg(feature = "midi")]
+    midi_device: "デバイス:",
+    #[cfg(feature = "midi")]
+    midi_none: "なし",
+    #[cfg(feature = "midi")]
+    midi_refresh: "デバイス一覧を更新",
+    #[cfg(feature = "midi")]
+    midi_notes: "ノート:",
+    #[cfg(feature = "midi")]
+    midi_any_note: "すべて",
+    #[cfg(feature = "midi")]
+    midi_notes_tip: "クリックするMIDIノート番号をカンマ区切りで指定します（中央のドは60）。空欄ならすべてのノートでクリックします。",
+    #[cfg(feature = "midi")]
+    midi_note_off_release: "ノートオフでリリース",
+    #[cfg(feature = "midi")]
+    midi_note_off_release_tip: "ノートオンで押下のみを送り、ノートオフでリリースを送るので、鍵盤を押している間入力が保持されます。オフの場合はノートオンごとに1回クリックします。",
+    toggle_hotkey: "切り替えホットキー:",
+    apply: "適用",
+    hotkey_hint: |hotkey| format!("{hotkey}でどこからでも送信のON/OFFを切り替え"),
+    stop_hotkey: "緊急停止:",
+    stop_hotkey_hint: |hotkey| format!("{hotkey}でどこからでも即座に停止してリリースを送信"),
+    hold_hotkey: "押している間送信:",
+    hold_hotkey_hint: |hotkey| format!("{hotkey}を押している間どこからでも送信し、離すと停止"),
+    capture_key: "キーを取得",
+    press_a_key: "キーを押してください…（Escで取消）",
+    capture_key_tip: "このウィンドウでキーを押して「押している間送信」のキーを入力し、適用します。空欄にするとキーホールドモードを無効にします。修飾キーなしのキーは、割り当て中は他のアプリで使えなくなります。",
+    stopped_banner: "緊急停止しました",
+    quick_launcher: "Quick LauncherのOSC設定値",
+    quick_launcher_tip: "VRChat Quick LauncherのOSC欄に貼り付けます。\
+        書式は「ポート:localhost:ポート」で、VRChatは1つ目のポートで受信し、\
+        自身のOSC出力をlocalhostのコンパニオンポートに送信します。",
+    companion_port: "コンパニオンポート:",
+    companion_port_tip: "VRChatがOSC出力を送信し、このアプリが受信するポートです。送信ポートとは独立して設定できます。",
+    template: "テンプレート:",
+    template_hover: "使える置換: {port}, {listen_port}",
+    template_missing_port: "テンプレートには{port}が必要です",
+    copy: "コピー",
+    paste_launcher: "貼り付け:",
+    paste_launcher_tip: "Quick Launcherの設定値を貼り付けると、最初のポートに送信し、最後のポートで受信します",
+    launcher_invalid: "ポート:ホスト:ポート の形式で入力してください（例: 9000:localhost:9001）",
+    script: "スクリプト",
+    script_hint: "スクリプトを読み込んでいる間は、クリックの代わりにスクリプトを再生します。形式: [{\"delay_ms\": 200, \"address\": \"/input/Jump\", \"value\": 1, \"type\": \"Int\"}, ...]",
+    load_script: "スクリプトを読み込む…",
+    script_loaded: |name, count, secs| format!("{name}: {count}イベント、{secs:.1}秒"),
+    unload_script: "スクリプトを解除してクリックに戻す",
+    loop_script: "スクリプトをループ再生",
+    record: "⏺ 記録",
+    stop_recording: "⏹ 記録を停止",
+    record_tip: "送信したすべてのメッセージをタイミングごと記録し、スクリプトとして保存したり再生したりできます。",
+    recorded_events: |count| format!("{count}イベントを記録済み"),
+    recording: "記録",
+    save_recording: "記録を保存…",
+    replay: "再生",
+    packet_bytes: "パケットのバイト列",
+    log: "ログ",
+    clear: "クリア",
+    export_csv: "CSVに書き出し",
+    exported: |count| format!("{count}件を書き出しました"),
+    export_failed: |error| format!("ログの書き出しに失敗しました: {error}"),
+    status_idle: "停止中",
+    status_paused: |packets| format!("一時停止中 · {packets}パケット"),
+    status_sending: |dests, interval, packets| {
+        format!("{dests}に{interval}ごとに送信中 · {packets}パケット")
+    },
+};
+
+/// egui's bundled fonts have no Japanese glyphs, so borrow an installed
+/// system font as a fallback. Without one, Japanese renders as boxes.
+const JAPANESE_FONTS: &[&str] = &[
+    "C:\\Windows\\Fonts\\YuGothM.ttc",
+    "C:\\Windows\\Fonts\\meiryo.ttc",
+    "C:\\Windows\\Fonts\\msgothic.ttc",
+    "/System/Library/Fonts/ヒラギノ角ゴシック W3.ttc",
+    "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+];
+
+pub fn install_japanese_font(ctx: &egui::Context) {
+    let Some(data) = JAPANESE_FONTS.iter().find_map(|path| fs::read(path).ok()) else {
+        return;
+    };
+
+    let mut fonts = egui::FontDefinitions::default();
+    fonts.font_data.insert(
+        "japanese".to_string(),
+        egui::FontData::from_owned(data).into(),
+    );
+    for family in [egui::FontFamily::Proportional, egui::FontFamily::Monospace] {
+        fonts
+            .families
+            .entry(family)
+            .or_default()
+            .push("japanese".to_string());
+    }
+    ctx.set_fonts(fonts);
+}
@@ -0,0 +1,12 @@
+//! OSC sending and receiving, independent of the GUI, so it can be reused by
+//! other front ends and tested on its own.
+
+pub mod http;
+pub mod notify;
+pub mod osc_core;
+pub mod oscquery;
+pub mod ping;
+pub mod receiver;
+pub mod resolve;
+pub mod script;
+pub mod vrchat;
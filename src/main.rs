@@ -1,191 +1,3260 @@
 #![windows_subsystem = "windows"]
 
-use std::net::UdpSocket;
-use std::sync::{Arc, Mutex};
-use std::thread;
-use std::time::Duration;
+use std::collections::VecDeque;
+use std::net::{IpAddr, Ipv4Addr};
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+use std::sync::{Arc, mpsc};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
 
+use chrono::{Local, TimeZone};
+use clap::Parser;
 use eframe::egui;
-use rosc::{OscMessage, OscPacket, OscType, encoder};
+use egui_plot::{Line, Plot, PlotPoints};
+use osc_sender::osc_core::{
+    AppState, ArgKind, BundleEntry, CHATBOX_ADDRESS, DestinationMode, Direction, INTERVAL_RANGE,
+    InputMode, Jitter, LogEntry, MAX_SEND_FAILURES, MAX_SEND_RETRIES, RATE_WINDOW_SECS, Settings,
+    Shared, Subdivision, Waveform, format_arg, format_minute_of_day, hex_dump, is_valid_address,
+    local_interfaces, minute_of_day, nudge_port, run_heartbeat, run_scheduler, run_sender,
+    write_log_csv,
+};
+use osc_sender::script::{Recording, Script, ScriptEvent};
+use osc_sender::{http, oscquery, ping, receiver, resolve, vrchat};
+use rosc::encoder;
 
-#[derive(Default)]
-struct AppState {
-    interval_ms: u64,
-    hold_ms: u64,
-    is_sending: bool,
-    dest_port: u16,
+use crate::i18n::Language;
+
+mod cli;
+mod config;
+mod hotkey;
+mod i18n;
+#[cfg(feature = "midi")]
+mod midi;
+#[cfg(feature = "tray")]
+mod tray;
+
+const CONFIG_SAVE_DELAY: Duration = Duration::from_secs(1);
+/// How often the lifetime stats are saved while sending, so a crash loses
+/// little.
+const STATS_SAVE_INTERVAL: Duration = Duration::from_secs(60);
+const UI_SCALE_RANGE: RangeInclusive<f32> = 0.75..=2.0;
+/// Window sizes before the UI scale is applied.
+const WINDOW_SIZE: egui::Vec2 = egui::vec2(300.0, 300.0);
+const COMPACT_SIZE: egui::Vec2 = egui::vec2(190.0, 40.0);
+const HOLD_RANGE: RangeInclusive<u64> = 10..=1000;
+const JITTER_STD_DEV_RANGE: RangeInclusive<u64> = 0..=500;
+const BURST_PAUSE_RANGE: RangeInclusive<u64> = 100..=10_000;
+const START_DELAY_RANGE: RangeInclusive<u64> = 0..=10_000;
+const SWEEP_PERIOD_RANGE: RangeInclusive<u64> = 100..=60_000;
+const HEARTBEAT_INTERVAL_RANGE: RangeInclusive<u64> = 100..=60_000;
+const MULTICAST_TTL_RANGE: RangeInclusive<u32> = 1..=255;
+const FLASH_DURATION: Duration = Duration::from_millis(100);
+const DETECT_TIMEOUT: Duration = Duration::from_secs(3);
+const RUNNING_COLOR: egui::Color32 = egui::Color32::from_rgb(0x3c, 0xb3, 0x71);
+/// How long the banner stays up after an emergency stop.
+const STOP_BANNER_DURATION: Duration = Duration::from_secs(2);
+/// Tap tempo averages over this many recent taps.
+const TAP_HISTORY: usize = 8;
+/// A gap longer than this between taps starts a new tap sequence.
+const TAP_RESET: Duration = Duration::from_secs(2);
+/// Segments in the sweep waveform preview.
+const PREVIEW_POINTS: u64 = 200;
+/// One-click intervals shown above the interval slider.
+const INTERVAL_PRESETS: [u64; 4] = [250, 500, 1000, 2000];
+
+struct OscSenderApp {
+    settings: Settings,
+    checked: bool,
+    ip_input: String,
+    /// Local addresses offered for `bind_ip`, refreshed on demand.
+    interfaces: Vec<(String, IpAddr)>,
+    port_inputs: Vec<String>,
+    /// Pending "Detect VRChat" lookup running on a background thread.
+    detect_rx: Option<mpsc::Receiver<Result<u16, String>>>,
+    detect_status: Option<Result<u16, String>>,
+    /// Cached hex dumps of the pressed and released packets, cleared whenever
+    /// the settings change.
+    hex_dumps: Option<[Result<String, String>; 2]>,
+    script_error: Option<String>,
+    address_input: String,
+    interval_in_hz: bool,
+    interval_hz: f64,
+    /// Recent tap-tempo presses, oldest first.
+    taps: VecDeque<Instant>,
+    quick_launcher_template: String,
+    /// A pasted Quick Launcher value to take the ports from.
+    launcher_input: String,
+    launcher_error: bool,
+    toggle_hotkey: String,
+    hotkey_input: String,
+    stop_hotkey: String,
+    stop_hotkey_input: String,
+    hold_hotkey: String,
+    hold_hotkey_input: String,
+    /// The next key pressed in the window becomes `hold_hotkey_input`.
+    capturing_hold_key: bool,
+    hotkeys: Option<hotkey::Hotkeys>,
+    hotkey_error: Option<String>,
+    #[cfg(feature = "tray")]
+    tray: Option<tray::Tray>,
+    #[cfg(feature = "tray")]
+    tray_error: Option<String>,
+    close_to_tray: bool,
+    midi_device: String,
+    midi_notes: Vec<u8>,
+    midi_note_off_release: bool,
+    #[cfg(feature = "midi")]
+    midi_notes_input: String,
+    #[cfg(feature = "midi")]
+    midi_devices: Vec<String>,
+    #[cfg(feature = "midi")]
+    midi: Option<midi::Midi>,
+    #[cfg(feature = "midi")]
+    midi_error: Option<String>,
+    /// Saved option to start sending on launch.
+    auto_start: bool,
+    /// Lifetime totals up to this launch, plus tabs closed since. The open
+    /// tabs' counts are added on top by [`Self::lifetime_stats`].
+    stats: config::Stats,
+    /// What the config file last got, so exiting only saves if it changed.
+    saved_stats: config::Stats,
+    stats_saved_at: Instant,
+    always_on_top: bool,
+    theme: config::Theme,
+    language: Language,
+    ui_scale: f32,
+    /// The Advanced section is expanded.
+    advanced_open: bool,
+    /// Only the send toggle and a status dot are shown.
+    compact: bool,
+    /// The window size before switching to compact, to restore.
+    full_size: Option<egui::Vec2>,
+    trusted_ips: Vec<IpAddr>,
+    trusted_hosts: Vec<String>,
+    /// The command line, and the main tab's settings as loaded before it
+    /// overrode them, so saving keeps the loaded values. Dropped once a
+    /// config is imported.
+    launch: Option<(cli::Cli, Settings)>,
+    /// Untrusted destination of a tab, waiting for confirmation before
+    /// sending starts there.
+    confirm_send: Option<(Arc<Shared>, String)>,
+    dont_ask_again: bool,
+    /// The broadcast toggle was switched on and waits for confirmation.
+    confirm_broadcast: bool,
+    /// Reset to defaults was clicked while sending and waits for
+    /// confirmation.
+    confirm_reset: bool,
+    presets: Vec<config::Preset>,
+    selected_preset: Option<usize>,
+    preset_name: String,
+    log_status: Option<String>,
+    config_dirty_since: Option<Instant>,
+    config_error: Option<String>,
+    /// Why the last config export or import failed.
+    config_file_error: Option<String>,
+    /// The main sender first, then the profiles.
+    tabs: Vec<Tab>,
+    selected_tab: usize,
+    /// The selected tab's sender, which `settings` is the UI copy of.
+    shared: Arc<Shared>,
+}
+
+/// A sender with its own settings and threads, shown as a tab. Every tab
+/// keeps running while another one is selected.
+struct Tab {
+    /// Unused for the main tab, whose settings are the top-level config.
+    name: String,
+    shared: Arc<Shared>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl Tab {
+    fn spawn(name: String, settings: Settings) -> Self {
+        let shared = Arc::new(Shared::new(settings));
+        let runners: [fn(&Shared); 8] = [
+            run_sender,
+            resolve::run_resolver,
+            receiver::run_receiver,
+            run_heartbeat,
+            run_scheduler,
+            vrchat::run_vrchat_watch,
+            ping::run_ping,
+            http::run_http,
+        ];
+        let threads = runners
+            .into_iter()
+            .map(|run| {
+                let shared = shared.clone();
+                thread::spawn(move || run(&shared))
+            })
+            .collect();
+        Self {
+            name,
+            shared,
+            threads,
+        }
+    }
+
+    /// Stops the threads, releasing any held input, and waits for them.
+    fn shut_down(self) {
+        self.shared.request_shutdown();
+        for thread in self.threads {
+            let _ = thread.join();
+        }
+    }
 }
 
-struct OscSenderApp {
-    interval_ms: u64,
-    hold_ms: u64,
-    checked: bool,
-    port: u16,
-    state: Arc<Mutex<AppState>>,
-}
+impl OscSenderApp {
+    fn new(cc: &eframe::CreationContext<'_>, config: config::Config, cli: &cli::Cli) -> Self {
+        let config::Config {
+            mut settings,
+            profiles,
+            presets,
+            interval_in_hz,
+            quick_launcher_template,
+            toggle_hotkey,
+            stop_hotkey,
+            hold_hotkey,
+            always_on_top,
+            theme,
+            language,
+            ui_scale,
+            advanced_open,
+            compact,
+            trusted_ips,
+            trusted_hosts,
+            close_to_tray,
+            midi_device,
+            midi_notes,
+            midi_note_off_release,
+            auto_start,
+            stats,
+        } = config;
+        i18n::install_japanese_font(&cc.egui_ctx);
+        if compact {
+            cc.egui_ctx
+                .send_viewport_cmd(egui::ViewportCommand::InnerSize(COMPACT_SIZE * ui_scale));
+        }
+        cc.egui_ctx.set_theme(theme.preference());
+        cc.egui_ctx.set_zoom_factor(ui_scale);
+        let launch = Some((cli.clone(), settings.clone()));
+        cli.apply(&mut settings);
+
+        let mut tabs = vec![Tab::spawn(String::new(), settings.clone())];
+        tabs.extend(
+            profiles
+                .into_iter()
+                .map(|profile| Tab::spawn(profile.name, profile.settings)),
+        );
+        let shared = tabs[0].shared.clone();
+
+        let (hotkeys, hotkey_error) = match hotkey::Hotkeys::new(shared.clone()) {
+            Ok(mut hotkeys) => {
+                let toggle = hotkeys.set_toggle(&toggle_hotkey);
+                let stop = hotkeys.set_stop(&stop_hotkey);
+                let hold = hotkeys.set_hold(&hold_hotkey);
+                (Some(hotkeys), toggle.and(stop).and(hold).err())
+            }
+            Err(e) => (None, Some(e)),
+        };
+
+        #[cfg(feature = "tray")]
+        let (tray, tray_error) = match tray::Tray::new(&cc.egui_ctx, shared.clone()) {
+            Ok(tray) => (Some(tray), None),
+            Err(e) => (None, Some(e)),
+        };
+
+        let mut app = Self {
+            ip_input: dest_input(&settings),
+            interfaces: local_interfaces(),
+            port_inputs: port_inputs(&settings),
+            detect_rx: None,
+            detect_status: None,
+            hex_dumps: None,
+            script_error: None,
+            address_input: settings.address.clone(),
+            interval_hz: 1000.0 / settings.interval_ms as f64,
+            settings,
+            checked: false,
+            interval_in_hz,
+            taps: VecDeque::with_capacity(TAP_HISTORY),
+            quick_launcher_template,
+            launcher_input: String::new(),
+            launcher_error: false,
+            hotkey_input: toggle_hotkey.clone(),
+            toggle_hotkey,
+            stop_hotkey_input: stop_hotkey.clone(),
+            stop_hotkey,
+            hold_hotkey_input: hold_hotkey.clone(),
+            hold_hotkey,
+            capturing_hold_key: false,
+            hotkeys,
+            hotkey_error,
+            #[cfg(feature = "tray")]
+            tray,
+            #[cfg(feature = "tray")]
+            tray_error,
+            close_to_tray,
+            #[cfg(feature = "midi")]
+            midi_notes_input: format_notes(&midi_notes),
+            #[cfg(feature = "midi")]
+            midi_devices: midi::devices().unwrap_or_default(),
+            #[cfg(feature = "midi")]
+            midi: None,
+            #[cfg(feature = "midi")]
+            midi_error: None,
+            midi_device,
+            midi_notes,
+            midi_note_off_release,
+            auto_start,
+            stats,
+            saved_stats: stats,
+            stats_saved_at: Instant::now(),
+            always_on_top,
+            theme,
+            language,
+            ui_scale,
+            advanced_open,
+            compact,
+            full_size: None,
+            trusted_ips,
+            trusted_hosts,
+            launch,
+            confirm_send: None,
+            confirm_broadcast: false,
+            confirm_reset: false,
+            dont_ask_again: false,
+            presets,
+            selected_preset: None,
+            preset_name: String::new(),
+            log_status: None,
+            config_dirty_since: None,
+            config_error: None,
+            config_file_error: None,
+            tabs,
+            selected_tab: 0,
+            shared,
+        };
+        app.sync_hotkey_profiles();
+        app.sync_trusted();
+        #[cfg(feature = "midi")]
+        app.connect_midi();
+        if cli.auto_start.unwrap_or(auto_start) {
+            app.start_sending();
+        }
+        app
+    }
+
+    /// Turns sending on, or asks for confirmation first when the destination
+    /// is another machine that isn't trusted yet.
+    fn start_sending(&mut self) {
+        let mut started = false;
+        self.shared.update(|s| started = s.request_start());
+        self.checked = started;
+    }
+
+    /// Hands the trusted machines to every tab, which check them whatever
+    /// starts sending.
+    fn sync_trusted(&self) {
+        for tab in &self.tabs {
+            tab.shared.update(|s| {
+                s.trusted_ips.clone_from(&self.trusted_ips);
+                s.trusted_hosts.clone_from(&self.trusted_hosts);
+            });
+        }
+    }
+
+    /// Pushes the UI copy of the settings to the sender thread and schedules
+    /// a config save.
+    fn push_settings(&mut self) {
+        let settings = self.settings.clone();
+        self.shared.update(|s| s.settings = settings);
+        self.hex_dumps = None;
+        self.mark_config_dirty();
+    }
+
+    fn mark_config_dirty(&mut self) {
+        self.config_dirty_since.get_or_insert_with(Instant::now);
+    }
+
+    fn save_config(&mut self) {
+        self.config_dirty_since = None;
+        let config = self.to_config();
+        self.saved_stats = config.stats;
+        self.stats_saved_at = Instant::now();
+        self.config_error = config::save(&config)
+            .err()
+            .map(|e| format!("Failed to save config: {e}"));
+    }
+
+    /// The saved totals with every open tab's clicks and runtime added.
+    fn lifetime_stats(&self) -> config::Stats {
+        let mut stats = self.stats;
+        for tab in &self.tabs {
+            add_tab_stats(&mut stats, &tab.shared.state.lock().unwrap());
+        }
+        stats
+    }
+
+    /// Shuts a tab down, keeping its counts in the lifetime stats.
+    fn retire_tab(&mut self, tab: Tab) {
+        add_tab_stats(&mut self.stats, &tab.shared.state.lock().unwrap());
+        tab.shut_down();
+    }
+
+    /// The current settings of a tab, which for the selected one are the UI
+    /// copy.
+    fn tab_settings(&self, index: usize) -> Settings {
+        if index == self.selected_tab {
+            self.settings.clone()
+        } else {
+            self.tabs[index]
+                .shared
+                .state
+                .lock()
+                .unwrap()
+                .settings
+                .clone()
+        }
+    }
+
+    fn to_config(&self) -> config::Config {
+        let mut settings = self.tab_settings(0);
+        if let Some((cli, loaded)) = &self.launch {
+            cli.unapply(&mut settings, loaded);
+        }
+        config::Config {
+            settings,
+            profiles: (1..self.tabs.len())
+                .map(|index| config::Profile {
+                    name: self.tabs[index].name.clone(),
+                    settings: self.tab_settings(index),
+                })
+                .collect(),
+            presets: self.presets.clone(),
+            interval_in_hz: self.interval_in_hz,
+            quick_launcher_template: self.quick_launcher_template.clone(),
+            toggle_hotkey: self.toggle_hotkey.clone(),
+            stop_hotkey: self.stop_hotkey.clone(),
+            hold_hotkey: self.hold_hotkey.clone(),
+            always_on_top: self.always_on_top,
+            theme: self.theme,
+            language: self.language,
+            ui_scale: self.ui_scale,
+            advanced_open: self.advanced_open,
+            compact: self.compact,
+            trusted_ips: self.trusted_ips.clone(),
+            trusted_hosts: self.trusted_hosts.clone(),
+            close_to_tray: self.close_to_tray,
+            midi_device: self.midi_device.clone(),
+            midi_notes: self.midi_notes.clone(),
+            midi_note_off_release: self.midi_note_off_release,
+            auto_start: self.auto_start,
+            stats: self.lifetime_stats(),
+        }
+    }
+
+    fn export_config(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("JSON", &["json"])
+            .set_file_name("osc-sender.json")
+            .save_file()
+        else {
+            return;
+        };
+        self.config_file_error = config::export(&self.to_config(), &path)
+            .err()
+            .map(|e| format!("Failed to export config: {e}"));
+    }
+
+    fn import_config(&mut self, ctx: &egui::Context) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("JSON", &["json"])
+            .pick_file()
+        else {
+            return;
+        };
+        match config::import(&path) {
+            Ok(config) => {
+                self.config_file_error = None;
+                self.apply_config(ctx, config);
+            }
+            Err(e) => self.config_file_error = Some(e),
+        }
+    }
+
+    /// Replaces the whole configuration, as if the app had been started
+    /// with it. The lifetime stats and trusted destinations stay as they
+    /// are.
+    fn apply_config(&mut self, ctx: &egui::Context, config: config::Config) {
+        let config::Config {
+            settings,
+            profiles,
+            presets,
+            interval_in_hz,
+            quick_launcher_template,
+            toggle_hotkey,
+            stop_hotkey,
+            hold_hotkey,
+            always_on_top,
+            theme,
+            language,
+            ui_scale,
+            advanced_open,
+            compact,
+            // Which machines to trust is this install's call too; an
+            // imported file can't waive the confirmation.
+            trusted_ips: _,
+            trusted_hosts: _,
+            close_to_tray,
+            midi_device,
+            midi_notes,
+            midi_note_off_release,
+            auto_start,
+            // Lifetime stats belong to this install, not to the imported file.
+            stats: _,
+        } = config;
+
+        self.launch = None;
+        self.select_tab(0);
+        let removed: Vec<Tab> = self.tabs.drain(1..).collect();
+        for tab in removed {
+            self.retire_tab(tab);
+        }
+        self.tabs.extend(
+            profiles
+                .into_iter()
+                .map(|profile| Tab::spawn(profile.name, profile.settings)),
+        );
+        self.sync_hotkey_profiles();
+        self.sync_trusted();
+        self.load_settings(settings);
+        self.presets = presets;
+        self.selected_preset = None;
+        self.interval_in_hz = interval_in_hz;
+        self.quick_launcher_template = quick_launcher_template;
+        if let Some(hotkeys) = &mut self.hotkeys {
+            let toggle = hotkeys.set_toggle(&toggle_hotkey);
+            let stop = hotkeys.set_stop(&stop_hotkey);
+            let hold = hotkeys.set_hold(&hold_hotkey);
+            self.hotkey_error = toggle.and(stop).and(hold).err();
+        }
+        self.hotkey_input = toggle_hotkey.clone();
+        self.toggle_hotkey = toggle_hotkey;
+        self.stop_hotkey_input = stop_hotkey.clone();
+        self.stop_hotkey = stop_hotkey;
+        self.hold_hotkey_input = hold_hotkey.clone();
+        self.hold_hotkey = hold_hotkey;
+        self.always_on_top = always_on_top;
+        ctx.send_viewport_cmd(egui::ViewportCommand::WindowLevel(window_level(
+            always_on_top,
+        )));
+        self.theme = theme;
+        ctx.set_theme(theme.preference());
+        self.language = language;
+        self.ui_scale = ui_scale;
+        self.advanced_open = advanced_open;
+        self.set_compact(ctx, compact);
+        ctx.set_zoom_factor(ui_scale);
+        self.close_to_tray = close_to_tray;
+        self.midi_device = midi_device;
+        self.midi_notes = midi_notes;
+        self.midi_note_off_release = midi_note_off_release;
+        #[cfg(feature = "midi")]
+        {
+            self.midi_notes_input = format_notes(&self.midi_notes);
+            self.connect_midi();
+        }
+        self.auto_start = auto_start;
+        self.push_settings();
+    }
+
+    /// Shows `settings` in the UI, resetting the text fields that edit them.
+    fn load_settings(&mut self, settings: Settings) {
+        self.ip_input = dest_input(&settings);
+        self.port_inputs = port_inputs(&settings);
+        self.address_input = settings.address.clone();
+        self.interval_hz = 1000.0 / settings.interval_ms as f64;
+        self.settings = settings;
+        self.hex_dumps = None;
+    }
+
+    fn select_tab(&mut self, index: usize) {
+        if index == self.selected_tab {
+            return;
+        }
+        self.selected_tab = index;
+        self.shared = self.tabs[index].shared.clone();
+        let settings = self.shared.state.lock().unwrap().settings.clone();
+        self.load_settings(settings);
+        self.checked = self.shared.state.lock().unwrap().is_sending;
+        self.confirm_send = None;
+        self.confirm_broadcast = false;
+        self.confirm_reset = false;
+    }
+
+    /// Puts the selected tab's settings back to their defaults. App-wide
+    /// options such as hotkeys and presets are kept.
+    fn reset_settings(&mut self) {
+        self.load_settings(Settings::default());
+        self.selected_preset = None;
+        self.push_settings();
+    }
+
+    /// Adds a tab with a copy of the selected tab's settings and selects it.
+    /// Listening, HTTP control, ping and the schedule start off in the copy:
+    /// two tabs can't bind the same port, and one schedule shouldn't start
+    /// both.
+    fn add_tab(&mut self) {
+        let name = (self.language.strings().profile_name)(self.tabs.len());
+        let mut settings = self.settings.clone();
+        settings.listen_enabled = false;
+        settings.http_enabled = false;
+        settings.ping_enabled = false;
+        settings.schedule.enabled = false;
+        self.tabs.push(Tab::spawn(name, settings));
+        self.sync_hotkey_profiles();
+        self.sync_trusted();
+        self.select_tab(self.tabs.len() - 1);
+        self.mark_config_dirty();
+    }
+
+    /// Stops and removes the selected tab. The main tab can't be removed.
+    fn remove_tab(&mut self) {
+        let index = self.selected_tab;
+        if index == 0 {
+            return;
+        }
+        self.select_tab(index - 1);
+        let tab = self.tabs.remove(index);
+        self.retire_tab(tab);
+        self.sync_hotkey_profiles();
+        self.mark_config_dirty();
+    }
+
+    fn sync_hotkey_profiles(&self) {
+        if let Some(hotkeys) = &self.hotkeys {
+            hotkeys.set_profiles(
+                self.tabs[1..]
+                    .iter()
+                    .map(|tab| tab.shared.clone())
+                    .collect(),
+            );
+        }
+    }
+
+    fn tabs_ui(&mut self, ui: &mut egui::Ui) {
+        let tr = self.language.strings();
+        let mut selected = self.selected_tab;
+        ui.horizontal_wrapped(|ui| {
+            for (index, tab) in self.tabs.iter().enumerate() {
+                let name = if index == 0 { tr.main_tab } else { &tab.name };
+                let mut text = egui::RichText::new(name);
+                if tab.shared.state.lock().unwrap().is_sending {
+                    text = text.color(RUNNING_COLOR);
+                }
+                ui.selectable_value(&mut selected, index, text);
+            }
+            if ui.button("+").on_hover_text(tr.add_tab_tip).clicked() {
+                selected = self.tabs.len();
+            }
+        });
+        if selected == self.tabs.len() {
+            self.add_tab();
+        } else {
+            self.select_tab(selected);
+        }
+
+        if self.selected_tab == 0 {
+            return;
+        }
+        let (mut renamed, mut remove) = (false, false);
+        ui.horizontal(|ui| {
+            ui.label(tr.tab_name);
+            renamed = ui
+                .add(
+                    egui::TextEdit::singleline(&mut self.tabs[self.selected_tab].name)
+                        .desired_width(120.0),
+                )
+                .changed();
+            remove = ui.button(tr.remove_tab).clicked();
+        });
+        if renamed {
+            self.mark_config_dirty();
+        }
+        if remove {
+            self.remove_tab();
+        }
+    }
+
+    fn apply_preset(&mut self, index: usize) {
+        let Some(preset) = self.presets.get(index) else {
+            return;
+        };
+        preset.apply(&mut self.settings);
+        self.address_input = self.settings.address.clone();
+        self.port_inputs = port_inputs(&self.settings);
+        self.selected_preset = Some(index);
+        self.push_settings();
+    }
+
+    fn presets_ui(&mut self, ui: &mut egui::Ui) {
+        let tr = self.language.strings();
+        ui.horizontal(|ui| {
+            ui.label(tr.preset);
+
+            let selected_name = self
+                .selected_preset
+                .and_then(|i| self.presets.get(i))
+                .map_or(tr.preset_none, |p| p.name.as_str());
+            let mut chosen = None;
+            egui::ComboBox::from_id_salt("preset")
+                .selected_text(selected_name)
+                .width(120.0)
+                .show_ui(ui, |ui| {
+                    for (i, preset) in self.presets.iter().enumerate() {
+                        if ui
+                            .selectable_label(self.selected_preset == Some(i), &preset.name)
+                            .clicked()
+                        {
+                            chosen = Some(i);
+                        }
+                    }
+                });
+            if let Some(i) = chosen {
+                self.apply_preset(i);
+            }
+
+            if ui
+                .add_enabled(
+                    self.selected_preset.is_some(),
+                    egui::Button::new(tr.delete_preset),
+                )
+                .clicked()
+                && let Some(i) = self.selected_preset.take()
+            {
+                self.presets.remove(i);
+                self.mark_config_dirty();
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.preset_name)
+                    .hint_text(tr.preset_name_hint)
+                    .desired_width(120.0),
+            );
+
+            let name = self.preset_name.trim();
+            if ui
+                .add_enabled(!name.is_empty(), egui::Button::new(tr.save_preset))
+                .clicked()
+            {
+                let preset = config::Preset::capture(name, &self.settings);
+                // Saving under an existing name overwrites that preset.
+                let index = match self.presets.iter().position(|p| p.name == preset.name) {
+                    Some(i) => {
+                        self.presets[i] = preset;
+                        i
+                    }
+                    None => {
+                        self.presets.push(preset);
+                        self.presets.len() - 1
+                    }
+                };
+                self.selected_preset = Some(index);
+                self.preset_name.clear();
+                self.mark_config_dirty();
+            }
+        });
+
+        ui.horizontal(|ui| {
+            if ui
+                .button(tr.export_config)
+                .on_hover_text(tr.export_config_tip)
+                .clicked()
+            {
+                self.export_config();
+            }
+            if ui
+                .add_enabled(!self.checked, egui::Button::new(tr.import_config))
+                .on_hover_text(tr.import_config_tip)
+                .clicked()
+            {
+                self.import_config(ui.ctx());
+            }
+            if ui
+                .button(tr.reset_defaults)
+                .on_hover_text(tr.reset_defaults_tip)
+                .clicked()
+            {
+                if self.checked {
+                    self.confirm_reset = true;
+                } else {
+                    self.reset_settings();
+                }
+            }
+        });
+        if let Some(error) = &self.config_file_error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+    }
+
+    fn send_toggle_ui(&mut self, ui: &mut egui::Ui) {
+        let tr = self.language.strings();
+        if ui
+            .checkbox(&mut self.checked, tr.send_osc)
+            .on_hover_text(tr.send_tip)
+            .changed()
+        {
+            if self.checked {
+                self.start_sending();
+            } else {
+                self.shared.update(|s| s.set_sending(false));
+            }
+        }
+    }
+
+    /// The whole panel in compact mode: the send toggle and a dot that
+    /// flashes on every send, for use as an overlay.
+    fn compact_ui(&mut self, ui: &mut egui::Ui) {
+        let tr = self.language.strings();
+        ui.horizontal(|ui| {
+            self.send_toggle_ui(ui);
+            let last_sent = self.shared.state.lock().unwrap().last_sent;
+            send_indicator(ui, last_sent);
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui
+                    .add(egui::Button::new("🗖").frame(false))
+                    .on_hover_text(tr.expand_layout)
+                    .clicked()
+                {
+                    self.set_compact(ui.ctx(), false);
+                }
+            });
+        });
+    }
+
+    /// Switches between the full and the compact layout, resizing the
+    /// window to fit.
+    fn set_compact(&mut self, ctx: &egui::Context, compact: bool) {
+        if compact == self.compact {
+            return;
+        }
+        self.compact = compact;
+        let size = if compact {
+            self.full_size = ctx
+                .input(|i| i.viewport().inner_rect)
+                .map(|rect| rect.size());
+            COMPACT_SIZE * self.ui_scale
+        } else {
+            self.full_size.unwrap_or(WINDOW_SIZE * self.ui_scale)
+        };
+        ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize(size));
+        self.mark_config_dirty();
+    }
+
+    fn main_ui(&mut self, ui: &mut egui::Ui) {
+        let tr = self.language.strings();
+        ui.horizontal(|ui| {
+            ui.heading("OSC Sender");
+            if ui
+                .checkbox(&mut self.always_on_top, tr.always_on_top)
+                .changed()
+            {
+                ui.ctx()
+                    .send_viewport_cmd(egui::ViewportCommand::WindowLevel(window_level(
+                        self.always_on_top,
+                    )));
+                self.mark_config_dirty();
+            }
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui
+                    .add(egui::Button::new("🗕").frame(false))
+                    .on_hover_text(tr.compact_mode)
+                    .clicked()
+                {
+                    self.set_compact(ui.ctx(), true);
+                }
+
+                let next = self.theme.next();
+                if ui
+                    .add(egui::Button::new(self.theme.icon()).frame(false))
+                    .on_hover_text((tr.theme_hover)(self.theme.label(tr), next.label(tr)))
+                    .clicked()
+                {
+                    self.theme = next;
+                    ui.ctx().set_theme(next.preference());
+                    self.mark_config_dirty();
+                }
+
+                let mut language = self.language;
+                egui::ComboBox::from_id_salt("language")
+                    .selected_text(language.label())
+                    .width(70.0)
+                    .show_ui(ui, |ui| {
+                        for value in Language::ALL {
+                            ui.selectable_value(&mut language, value, value.label());
+                        }
+                    })
+                    .response
+                    .on_hover_text(tr.language);
+                if language != self.language {
+                    self.language = language;
+                    self.mark_config_dirty();
+                }
+            });
+        });
+
+        let scale = ui.add(
+            egui::Slider::new(&mut self.ui_scale, UI_SCALE_RANGE)
+                .step_by(0.05)
+                .text(tr.ui_scale),
+        );
+        // Rescaling moves the slider under the pointer, so wait for the drag
+        // to finish.
+        if scale.drag_stopped() || (scale.changed() && !scale.dragged()) {
+            ui.ctx().set_zoom_factor(self.ui_scale);
+            self.mark_config_dirty();
+        }
+
+        self.tabs_ui(ui);
+        self.presets_ui(ui);
+        ui.separator();
+
+        if ui
+            .checkbox(&mut self.settings.randomize_interval, tr.randomize_interval)
+            .changed()
+        {
+            self.push_settings();
+        }
+
+        if self.settings.randomize_interval {
+            ui.horizontal(|ui| {
+                let mut changed = false;
+                for jitter in Jitter::ALL {
+                    changed |= ui
+                        .radio_value(&mut self.settings.jitter, jitter, (tr.jitter_name)(jitter))
+                        .changed();
+                }
+                if changed {
+                    self.push_settings();
+                }
+            });
+        }
+        if self.settings.randomize_interval && self.settings.jitter == Jitter::Gaussian {
+            self.interval_ui(ui);
+            if slider_entry(
+                ui,
+                &mut self.settings.jitter_std_dev_ms,
+                JITTER_STD_DEV_RANGE,
+                tr.jitter_std_dev,
+            )
+            .on_hover_text(tr.jitter_std_dev_tip)
+            .changed()
+            {
+                self.push_settings();
+            }
+        } else if self.settings.randomize_interval {
+            let min = slider_entry(
+                ui,
+                &mut self.settings.interval_min_ms,
+                INTERVAL_RANGE,
+                tr.min_interval,
+            )
+            .on_hover_text(tr.random_interval_tip);
+            let max = slider_entry(
+                ui,
+                &mut self.settings.interval_max_ms,
+                INTERVAL_RANGE,
+                tr.max_interval,
+            )
+            .on_hover_text(tr.random_interval_tip);
+            if min.changed() {
+                self.settings.interval_max_ms = self
+                    .settings
+                    .interval_max_ms
+                    .max(self.settings.interval_min_ms);
+            }
+            if max.changed() {
+                self.settings.interval_min_ms = self
+                    .settings
+                    .interval_min_ms
+                    .min(self.settings.interval_max_ms);
+            }
+            if min.changed() || max.changed() {
+                self.push_settings();
+            }
+        } else {
+            self.interval_ui(ui);
+            self.tap_tempo_ui(ui);
+            self.bpm_ui(ui);
+        }
+        self.ramp_ui(ui);
+
+        self.input_mode_ui(ui);
+
+        if ui
+            .checkbox(&mut self.settings.burst_mode, tr.burst_mode)
+            .changed()
+        {
+            self.push_settings();
+        }
+
+        if self.settings.burst_mode {
+            let count = slider_entry(ui, &mut self.settings.burst_count, 1..=50, tr.burst_count);
+            let pause = slider_entry(
+                ui,
+                &mut self.settings.burst_pause_ms,
+                BURST_PAUSE_RANGE,
+                tr.burst_pause,
+            );
+            if count.changed() || pause.changed() {
+                self.push_settings();
+            }
+        }
+
+        ui.horizontal(|ui| {
+            ui.label(tr.max_packets_per_sec);
+            if ui
+                .add(egui::DragValue::new(&mut self.settings.max_packets_per_sec).range(0..=10_000))
+                .on_hover_text(tr.max_packets_per_sec_tip)
+                .changed()
+            {
+                self.push_settings();
+            }
+        });
+        if self.settings.rate_limited() {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                (tr.rate_limited)(self.settings.min_click_period_ms()),
+            );
+        }
+        if self.settings.hold_exceeds_interval() {
+            ui.colored_label(ui.visuals().warn_fg_color, tr.hold_exceeds_interval);
+        }
+
+        ui.horizontal(|ui| {
+            ui.label(tr.osc_address);
+
+            let valid = is_valid_address(&self.address_input);
+            let text_color = (!valid).then(|| ui.visuals().weak_text_color());
+            if ui
+                .add(
+                    egui::TextEdit::singleline(&mut self.address_input)
+                        .text_color_opt(text_color)
+                        .desired_width(150.0),
+                )
+                .changed()
+                && is_valid_address(&self.address_input)
+            {
+                self.settings.address = self.address_input.clone();
+                self.push_settings();
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(tr.release_address);
+            let address = &self.settings.release_address;
+            let valid = address.is_empty() || is_valid_address(address);
+            let text_color = (!valid).then(|| ui.visuals().weak_text_color());
+            if ui
+                .add(
+                    egui::TextEdit::singleline(&mut self.settings.release_address)
+                        .hint_text(tr.release_address_hint)
+                        .text_color_opt(text_color)
+                        .desired_width(150.0),
+                )
+                .on_hover_text(tr.release_address_tip)
+                .changed()
+            {
+                self.push_settings();
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(tr.arg_type);
+            for kind in ArgKind::ALL {
+                if ui
+                    .radio_value(&mut self.settings.arg_kind, kind, kind.label())
+                    .changed()
+                {
+                    self.push_settings();
+                }
+            }
+        });
+
+        ui.add_enabled_ui(self.settings.arg_kind != ArgKind::Bool, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(tr.pressed);
+                let on = ui.add(egui::DragValue::new(&mut self.settings.on_value).speed(0.1));
+                ui.label(tr.released);
+                let off = ui.add(egui::DragValue::new(&mut self.settings.off_value).speed(0.1));
+                if on.changed() || off.changed() {
+                    self.push_settings();
+                }
+            });
+        });
+
+        if ui
+            .checkbox(&mut self.settings.append_sequence, tr.append_sequence)
+            .on_hover_text(tr.append_sequence_tip)
+            .changed()
+        {
+            self.push_settings();
+        }
+
+        self.bundle_ui(ui);
+        self.cycle_ui(ui);
+        self.sweep_ui(ui);
+        self.text_ui(ui);
+
+        ui.horizontal(|ui| {
+            ui.label(tr.click_limit);
+            if ui
+                .add(egui::DragValue::new(&mut self.settings.click_limit).range(0..=100_000))
+                .on_hover_text(tr.no_limit_hover)
+                .changed()
+            {
+                self.push_settings();
+            }
+
+            let clicks_sent = self.shared.state.lock().unwrap().clicks_sent;
+            if self.settings.click_limit > 0 {
+                ui.label((tr.clicks_sent_of)(clicks_sent, self.settings.click_limit));
+            } else {
+                ui.label((tr.clicks_sent)(clicks_sent));
+            }
+        });
+        if let Some((packets, bytes)) = self.settings.run_estimate() {
+            ui.weak((tr.run_estimate)(packets, &format_bytes(bytes)));
+        }
+
+        ui.horizontal(|ui| {
+            ui.label(tr.max_runtime);
+            if ui
+                .add(egui::DragValue::new(&mut self.settings.max_runtime_secs).range(0..=86_400))
+                .on_hover_text(tr.no_limit_hover)
+                .changed()
+            {
+                self.push_settings();
+            }
+
+            let remaining = self.shared.state.lock().unwrap().runtime_remaining();
+            if let Some(remaining) = remaining {
+                ui.label((tr.stops_in)(remaining.as_secs_f32().ceil()));
+            }
+        });
+
+        if slider_entry(
+            ui,
+            &mut self.settings.start_delay_ms,
+            START_DELAY_RANGE,
+            tr.start_delay,
+        )
+        .changed()
+        {
+            self.push_settings();
+        }
+        if ui
+            .checkbox(&mut self.auto_start, tr.auto_start)
+            .on_hover_text(tr.auto_start_tip)
+            .changed()
+        {
+            self.mark_config_dirty();
+        }
+
+        self.schedule_ui(ui);
+
+        ui.horizontal(|ui| {
+            self.send_toggle_ui(ui);
+            let (paused, vrchat_missing, host_unresolved, unfocused) = {
+                let state = self.shared.state.lock().unwrap();
+                (
+                    state.paused,
+                    state.is_paused() && state.vrchat_missing,
+                    state.is_paused() && state.host_unresolved,
+                    state.is_paused() && state.unfocused,
+                )
+            };
+            let label = if paused { tr.resume } else { tr.pause };
+            if ui
+                .add_enabled(self.checked, egui::Button::new(label))
+                .clicked()
+            {
+                self.shared.update(|s| s.set_paused(!paused));
+            }
+            if ui.button(tr.send_single_click).clicked() {
+                self.shared.request_click();
+            }
+
+            let starting_at = self.shared.state.lock().unwrap().starting_at;
+            if paused {
+                ui.label(tr.paused);
+            } else if vrchat_missing {
+                ui.label(tr.paused_no_vrchat);
+            } else if host_unresolved {
+                ui.label(tr.paused_unresolved);
+            } else if unfocused {
+                ui.label(tr.paused_unfocused);
+            } else if let Some(at) = starting_at {
+                let left = at.saturating_duration_since(Instant::now());
+                ui.label((tr.starting_in)(left.as_secs_f32().ceil()));
+            }
+        });
+        self.next_click_ui(ui);
+
+        if ui
+            .checkbox(
+                &mut self.settings.pause_when_unfocused,
+                tr.pause_when_unfocused,
+            )
+            .on_hover_text(tr.pause_when_unfocused_tip)
+            .changed()
+        {
+            self.push_settings();
+        }
+        if ui
+            .checkbox(&mut self.settings.notify, tr.notify)
+            .on_hover_text(tr.notify_tip)
+            .changed()
+        {
+            self.push_settings();
+        }
+        self.vrchat_ui(ui);
+
+        if ui
+            .checkbox(&mut self.settings.dry_run, tr.dry_run)
+            .on_hover_text(tr.dry_run_tip)
+            .changed()
+        {
+            self.push_settings();
+        }
+
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.label(tr.destination_ip);
+
+            let input = self.ip_input.trim();
+            let parsed = IpAddr::from_str(input);
+            let valid = parsed.is_ok() || resolve::is_valid_hostname(input);
+            let text_color = (!valid).then(|| ui.visuals().weak_text_color());
+            let response = ui.add_enabled(
+                !self.checked,
+                egui::TextEdit::singleline(&mut self.ip_input)
+                    .text_color_opt(text_color)
+                    .desired_width(120.0),
+            );
+            let response = response.on_hover_text(tr.destination_ip_tip);
+            let input = self.ip_input.trim();
+            if response.changed() {
+                if let Ok(ip) = IpAddr::from_str(input) {
+                    self.settings.dest_ip = ip;
+                    self.settings.dest_host.clear();
+                    self.push_settings();
+                } else if resolve::is_valid_hostname(input) {
+                    self.settings.dest_host = input.to_string();
+                    self.push_settings();
+                }
+            }
+            if !self.settings.dest_host.is_empty() {
+                // The resolver thread owns `dest_ip` while a hostname is set.
+                self.settings.dest_ip = self.shared.state.lock().unwrap().settings.dest_ip;
+                ui.weak(format!("→ {}", self.settings.dest_ip));
+            }
+            if ui
+                .add_enabled(
+                    !self.checked,
+                    egui::Checkbox::new(&mut self.settings.multicast, tr.multicast),
+                )
+                .on_hover_text(tr.multicast_tip)
+                .changed()
+            {
+                self.push_settings();
+            }
+            let mut broadcast = self.settings.broadcast;
+            if ui
+                .add_enabled(
+                    !self.checked,
+                    egui::Checkbox::new(&mut broadcast, tr.broadcast),
+                )
+                .on_hover_text(tr.broadcast_tip)
+                .changed()
+            {
+                if broadcast {
+                    self.confirm_broadcast = true;
+                } else {
+                    self.settings.broadcast = false;
+                    self.push_settings();
+                }
+            }
+            if self.settings.broadcast
+                && ui
+                    .add_enabled(!self.checked, egui::Button::new("255.255.255.255"))
+                    .on_hover_text(tr.limited_broadcast_tip)
+                    .clicked()
+            {
+                self.settings.dest_ip = Ipv4Addr::BROADCAST.into();
+                self.settings.dest_host.clear();
+                self.ip_input = dest_input(&self.settings);
+                self.push_settings();
+            }
+        });
+        let host_error = self.shared.state.lock().unwrap().host_error.clone();
+        if let Some(error) = host_error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+        self.multicast_ui(ui);
+
+        self.ports_ui(ui);
+        self.listen_ui(ui);
+        self.http_ui(ui);
+        self.advanced_ui(ui);
+
+        let (packets_sent, last_sent, last_error, send_failures) = {
+            let state = self.shared.state.lock().unwrap();
+            (
+                state.packets_sent,
+                state.last_sent,
+                state.last_error.clone(),
+                state.send_failures,
+            )
+        };
+        ui.horizontal(|ui| {
+            send_indicator(ui, last_sent);
+            match last_sent {
+                Some(at) => ui.label((tr.packets_sent)(packets_sent, at.elapsed().as_secs_f32())),
+                None => ui.label(tr.no_packets),
+            };
+        });
+        self.send_rate_ui(ui);
+        self.stats_ui(ui);
+
+        if send_failures >= MAX_SEND_FAILURES {
+            ui.colored_label(ui.visuals().warn_fg_color, (tr.send_failing)(send_failures));
+        }
+        for error in last_error.iter().chain(&self.config_error) {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+        #[cfg(feature = "tray")]
+        if let Some(error) = &self.tray_error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+        #[cfg(feature = "tray")]
+        if self.tray.is_some()
+            && ui
+                .checkbox(&mut self.close_to_tray, tr.close_to_tray)
+                .on_hover_text(tr.close_to_tray_tip)
+                .changed()
+        {
+            self.mark_config_dirty();
+        }
+
+        self.hotkey_ui(ui);
+        #[cfg(feature = "midi")]
+        self.midi_ui(ui);
+
+        self.quick_launcher_ui(ui);
+
+        self.script_ui(ui);
+        self.hex_dump_ui(ui);
+
+        ui.separator();
+        self.log_ui(ui);
+    }
+
+    fn load_script(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("JSON", &["json"])
+            .pick_file()
+        else {
+            return;
+        };
+        match Script::load(&path) {
+            Ok(script) => {
+                self.script_error = None;
+                self.shared.update(|s| s.script = Some(Arc::new(script)));
+            }
+            Err(e) => self.script_error = Some(e),
+        }
+    }
+
+    fn recorded_events(&self) -> Vec<ScriptEvent> {
+        let state = self.shared.state.lock().unwrap();
+        state
+            .recording
+            .as_ref()
+            .map_or_else(Vec::new, |r| r.events.clone())
+    }
+
+    fn save_recording(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("JSON", &["json"])
+            .set_file_name("recording.json")
+            .save_file()
+        else {
+            return;
+        };
+        self.script_error = Script::save(&path, &self.recorded_events()).err();
+    }
+
+    /// Loads the recording as the script and starts playing it.
+    fn replay_recording(&mut self) {
+        let script = Script {
+            name: self.language.strings().recording.to_string(),
+            events: self.recorded_events(),
+        };
+        self.script_error = None;
+        let mut started = false;
+        self.shared.update(|s| {
+            s.script = Some(Arc::new(script));
+            started = s.request_start();
+        });
+        self.checked = started;
+    }
+
+    fn script_ui(&mut self, ui: &mut egui::Ui) {
+        let tr = self.language.strings();
+        egui::CollapsingHeader::new(tr.script).show(ui, |ui| {
+            ui.weak(tr.script_hint);
+            let script = self.shared.state.lock().unwrap().script.clone();
+            ui.add_enabled_ui(!self.checked, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button(tr.load_script).clicked() {
+                        self.load_script();
+                    }
+                    if let Some(script) = &script {
+                        ui.label((tr.script_loaded)(
+                            &script.name,
+                            script.events.len(),
+                            script.duration().as_secs_f32(),
+                        ));
+                        if ui.button("✕").on_hover_text(tr.unload_script).clicked() {
+                            self.shared.update(|s| s.script = None);
+                        }
+                    }
+                });
+            });
+            if ui
+                .checkbox(&mut self.settings.script_loop, tr.loop_script)
+                .changed()
+            {
+                self.push_settings();
+            }
+
+            let (recording, recorded) = {
+                let state = self.shared.state.lock().unwrap();
+                let recording = state.recording.as_ref();
+                (
+                    recording.is_some_and(|r| r.active),
+                    recording.map_or(0, |r| r.events.len()),
+                )
+            };
+            ui.horizontal(|ui| {
+                let label = if recording {
+                    tr.stop_recording
+                } else {
+                    tr.record
+                };
+                if ui.button(label).on_hover_text(tr.record_tip).clicked() {
+                    self.shared.update(|s| {
+                        if recording {
+                            if let Some(r) = &mut s.recording {
+                                r.active = false;
+                            }
+                        } else {
+                            s.recording = Some(Recording::new());
+                        }
+                    });
+                }
+                ui.label((tr.recorded_events)(recorded));
+            });
+            ui.add_enabled_ui(recorded > 0 && !recording, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button(tr.save_recording).clicked() {
+                        self.save_recording();
+                    }
+                    if ui
+                        .add_enabled(!self.checked, egui::Button::new(tr.replay))
+                        .clicked()
+                    {
+                        self.replay_recording();
+                    }
+                });
+            });
+
+            if let Some(error) = &self.script_error {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+            }
+        });
+    }
+
+    fn hex_dump_ui(&mut self, ui: &mut egui::Ui) {
+        let tr = self.language.strings();
+        egui::CollapsingHeader::new(tr.packet_bytes).show(ui, |ui| {
+            let settings = &self.settings;
+            let dumps = self.hex_dumps.get_or_insert_with(|| {
+                [true, false].map(|pressed| {
+                    encoder::encode(&settings.click_packet(pressed))
+                        .map(|bytes| hex_dump(&bytes))
+                        .map_err(|e| format!("Failed to encode OSC: {e}"))
+                })
+            });
+            for (label, dump) in [tr.pressed, tr.released].into_iter().zip(dumps.iter()) {
+                ui.label(label);
+                match dump {
+                    Ok(dump) => ui.monospace(dump),
+                    Err(error) => ui.colored_label(ui.visuals().error_fg_color, error),
+                };
+            }
+        });
+    }
+
+    /// A thin bar filling up from one click to the next.
+    fn next_click_ui(&self, ui: &mut egui::Ui) {
+        let progress = {
+            let state = self.shared.state.lock().unwrap();
+            match state.next_click {
+                Some((from, to)) if !state.is_paused() => {
+                    let total = to.saturating_duration_since(from).as_secs_f32();
+                    if total > 0.0 {
+                        (from.elapsed().as_secs_f32() / total).min(1.0)
+                    } else {
+                        1.0
+                    }
+                }
+                _ => 0.0,
+            }
+        };
+        ui.add(egui::ProgressBar::new(progress).desired_height(4.0));
+    }
+
+    fn confirm_send_ui(&mut self, ctx: &egui::Context) {
+        if self.confirm_send.is_none() {
+            // Whichever trigger asked, the question is asked here.
+            self.confirm_send = self.tabs.iter().find_map(|tab| {
+                let dest = tab.shared.state.lock().unwrap().confirm_start.take()?;
+                Some((tab.shared.clone(), dest))
+            });
+            self.dont_ask_again = false;
+        }
+        let Some((shared, dest)) = self.confirm_send.clone() else {
+            return;
+        };
+        let tr = self.language.strings();
+        let mut answer = None;
+        let modal = egui::Modal::new(egui::Id::new("confirm_send")).show(ctx, |ui| {
+            ui.heading(tr.confirm_send_title);
+            ui.label((tr.confirm_send_body)(&dest));
+            ui.checkbox(&mut self.dont_ask_again, tr.dont_ask_again);
+            ui.horizontal(|ui| {
+                if ui.button(tr.send_anyway).clicked() {
+                    answer = Some(true);
+                }
+                if ui.button(tr.cancel).clicked() {
+                    answer = Some(false);
+                }
+            });
+        });
+        if modal.should_close() {
+            answer.get_or_insert(false);
+        }
+
+        let Some(confirmed) = answer else {
+            return;
+        };
+        self.confirm_send = None;
+        if confirmed {
+            if self.dont_ask_again {
+                match dest.parse() {
+                    Ok(ip) => self.trusted_ips.push(ip),
+                    Err(_) => self.trusted_hosts.push(dest),
+                }
+                self.sync_trusted();
+                self.mark_config_dirty();
+            }
+            shared.update(|s| s.set_sending(true));
+            self.checked = self.shared.state.lock().unwrap().is_sending;
+        }
+    }
+
+    fn confirm_broadcast_ui(&mut self, ctx: &egui::Context) {
+        if !self.confirm_broadcast {
+            return;
+        }
+        let tr = self.language.strings();
+        let mut answer = None;
+        let modal = egui::Modal::new(egui::Id::new("confirm_broadcast")).show(ctx, |ui| {
+            ui.heading(tr.confirm_broadcast_title);
+            ui.label(tr.confirm_broadcast_body);
+            ui.horizontal(|ui| {
+                if ui.button(tr.enable_broadcast).clicked() {
+                    answer = Some(true);
+                }
+                if ui.button(tr.cancel).clicked() {
+                    answer = Some(false);
+                }
+            });
+        });
+        if modal.should_close() {
+            answer.get_or_insert(false);
+        }
+
+        let Some(confirmed) = answer else {
+            return;
+        };
+        self.confirm_broadcast = false;
+        if confirmed {
+            self.settings.broadcast = true;
+            self.push_settings();
+        }
+    }
+
+    fn confirm_reset_ui(&mut self, ctx: &egui::Context) {
+        if !self.confirm_reset {
+            return;
+        }
+        let tr = self.language.strings();
+        let mut answer = None;
+        let modal = egui::Modal::new(egui::Id::new("confirm_reset")).show(ctx, |ui| {
+            ui.heading(tr.confirm_reset_title);
+            ui.label(tr.confirm_reset_body);
+            ui.horizontal(|ui| {
+                if ui.button(tr.reset).clicked() {
+                    answer = Some(true);
+                }
+                if ui.button(tr.cancel).clicked() {
+                    answer = Some(false);
+                }
+            });
+        });
+        if modal.should_close() {
+            answer.get_or_insert(false);
+        }
+
+        let Some(confirmed) = answer else {
+            return;
+        };
+        self.confirm_reset = false;
+        if confirmed {
+            self.reset_settings();
+        }
+    }
+
+    /// One line summarizing what the sender thread is doing right now.
+    fn status_ui(&self, ui: &mut egui::Ui) {
+        let tr = self.language.strings();
+        let state = self.shared.state.lock().unwrap();
+        let status = if !state.is_sending {
+            tr.status_idle.to_string()
+        } else if state.is_paused() {
+            (tr.status_paused)(state.packets_sent)
+        } else {
+            let settings = &state.settings;
+            let dests: Vec<String> = settings
+                .destinations()
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+            let interval = if !settings.randomize_interval {
+                format!("{}ms", settings.interval_ms)
+            } else if settings.jitter == Jitter::Gaussian {
+                format!("{}±{}ms", settings.interval_ms, settings.jitter_std_dev_ms)
+            } else {
+                format!(
+                    "{}–{}ms",
+                    settings.interval_min_ms, settings.interval_max_ms
+                )
+            };
+            (tr.status_sending)(&dests.join(", "), &interval, state.packets_sent)
+        };
+        ui.label(status);
+    }
+
+    fn export_log(&mut self) {
+        let tr = self.language.strings();
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("CSV", &["csv"])
+            .set_file_name("osc-log.csv")
+            .save_file()
+        else {
+            return;
+        };
+
+        let entries: Vec<LogEntry> = self
+            .shared
+            .state
+            .lock()
+            .unwrap()
+            .log
+            .iter()
+            .cloned()
+            .collect();
+        self.log_status = Some(match write_log_csv(&path, &entries) {
+            Ok(()) => (tr.exported)(entries.len()),
+            Err(e) => (tr.export_failed)(&e.to_string()),
+        });
+    }
+
+    fn log_ui(&mut self, ui: &mut egui::Ui) {
+        let tr = self.language.strings();
+        egui::CollapsingHeader::new(tr.log).show(ui, |ui| {
+            ui.horizontal(|ui| {
+                if ui.button(tr.clear).clicked() {
+                    self.shared.state.lock().unwrap().log.clear();
+                }
+                if ui.button(tr.export_csv).clicked() {
+                    self.export_log();
+                }
+            });
+            if let Some(status) = &self.log_status {
+                ui.label(status);
+            }
+
+            let state = self.shared.state.lock().unwrap();
+            egui::ScrollArea::vertical()
+                .max_height(150.0)
+                .stick_to_bottom(true)
+                .show(ui, |ui| {
+                    for entry in &state.log {
+                        let (arrow, color) = match entry.direction {
+                            Direction::Sent => ("→", ui.visuals().text_color()),
+                            Direction::Received => ("←", ui.visuals().hyperlink_color),
+                        };
+                        let line = format!(
+                            "-{:>6.1}s {arrow} {}  {}{}",
+                            entry.at.elapsed().as_secs_f32(),
+                            entry.address,
+                            format_arg(&entry.value),
+                            if entry.dry_run { "  (dry)" } else { "" }
+                        );
+                        ui.label(egui::RichText::new(line).monospace().color(color));
+                    }
+                });
+        });
+    }
+
+    /// Reopens the MIDI device with the current mapping. MIDI drives the
+    /// main tab, like the hotkeys.
+    #[cfg(feature = "midi")]
+    fn connect_midi(&mut self) {
+        // Close first, as some backends can't open a device twice.
+        self.midi = None;
+        self.midi_error = None;
+        if self.midi_device.is_empty() {
+            return;
+        }
+        match midi::Midi::connect(
+            &self.midi_device,
+            self.tabs[0].shared.clone(),
+            self.midi_notes.clone(),
+            self.midi_note_off_release,
+        ) {
+            Ok(midi) => self.midi = Some(midi),
+            Err(e) => self.midi_error = Some(e),
+        }
+    }
+
+    #[cfg(feature = "midi")]
+    fn midi_ui(&mut self, ui: &mut egui::Ui) {
+        let tr = self.language.strings();
+        egui::CollapsingHeader::new("MIDI")
+            .id_salt("midi")
+            .show(ui, |ui| {
+                let mut changed = false;
+                ui.horizontal(|ui| {
+                    ui.label(tr.midi_device);
+                    let selected = if self.midi_device.is_empty() {
+                        tr.midi_none
+                    } else {
+                        &self.midi_device
+                    };
+                    egui::ComboBox::from_id_salt("midi_device")
+                        .selected_text(selected.to_string())
+                        .show_ui(ui, |ui| {
+                            changed |= ui
+                                .selectable_value(
+                                    &mut self.midi_device,
+                                    String::new(),
+                                    tr.midi_none,
+                                )
+                                .changed();
+                            for device in &self.midi_devices {
+                                changed |= ui
+                                    .selectable_value(&mut self.midi_device, device.clone(), device)
+                                    .changed();
+                            }
+                        });
+                    if ui.button("⟳").on_hover_text(tr.midi_refresh).clicked() {
+                        match midi::devices() {
+                            Ok(devices) => self.midi_devices = devices,
+                            Err(e) => self.midi_error = Some(e),
+                        }
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label(tr.midi_notes);
+                    let notes = parse_notes(&self.midi_notes_input);
+                    let text_color = notes.is_none().then(|| ui.visuals().error_fg_color);
+                    let response = ui
+                        .add(
+                            egui::TextEdit::singleline(&mut self.midi_notes_input)
+                                .hint_text(tr.midi_any_note)
+                                .text_color_opt(text_color)
+                                .desired_width(110.0),
+                        )
+                        .on_hover_text(tr.midi_notes_tip);
+                    if response.changed()
+                        && let Some(notes) = parse_notes(&self.midi_notes_input)
+                    {
+                        self.midi_notes = notes;
+                        changed = true;
+                    }
+                });
+                changed |= ui
+                    .checkbox(&mut self.midi_note_off_release, tr.midi_note_off_release)
+                    .on_hover_text(tr.midi_note_off_release_tip)
+                    .changed();
+
+                if changed {
+                    self.connect_midi();
+                    self.mark_config_dirty();
+                }
+                if let Some(error) = &self.midi_error {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                }
+            });
+    }
+
+    fn hotkey_ui(&mut self, ui: &mut egui::Ui) {
+        let tr = self.language.strings();
+        let enabled = self.hotkeys.is_some();
+        if hotkey_row(
+            ui,
+            tr,
+            tr.toggle_hotkey,
+            &mut self.hotkey_input,
+            &self.toggle_hotkey,
+            enabled,
+        ) && let Some(hotkeys) = &mut self.hotkeys
+        {
+            let binding = self.hotkey_input.trim().to_string();
+            self.hotkey_error = hotkeys.set_toggle(&binding).err();
+            self.toggle_hotkey = binding;
+            self.mark_config_dirty();
+        }
+        if hotkey_row(
+            ui,
+            tr,
+            tr.stop_hotkey,
+            &mut self.stop_hotkey_input,
+            &self.stop_hotkey,
+            enabled,
+        ) && let Some(hotkeys) = &mut self.hotkeys
+        {
+            let binding = self.stop_hotkey_input.trim().to_string();
+            self.hotkey_error = hotkeys.set_stop(&binding).err();
+            self.stop_hotkey = binding;
+            self.mark_config_dirty();
+        }
+        if hotkey_row(
+            ui,
+            tr,
+            tr.hold_hotkey,
+            &mut self.hold_hotkey_input,
+            &self.hold_hotkey,
+            enabled,
+        ) && let Some(hotkeys) = &mut self.hotkeys
+        {
+            let binding = self.hold_hotkey_input.trim().to_string();
+            self.hotkey_error = hotkeys.set_hold(&binding).err();
+            self.hold_hotkey = binding;
+            self.mark_config_dirty();
+        }
+
+        if self.capturing_hold_key {
+            let captured = ui.input(|i| {
+                i.events.iter().find_map(|event| match event {
+                    egui::Event::Key {
+                        key,
+                        pressed: true,
+                        modifiers,
+                        ..
+                    } => Some((*key, *modifiers)),
+                    _ => None,
+                })
+            });
+            if let Some((key, modifiers)) = captured {
+                self.capturing_hold_key = false;
+                if key != egui::Key::Escape {
+                    self.hold_hotkey_input = hotkey_name(key, modifiers);
+                }
+            }
+        }
+        let capture = if self.capturing_hold_key {
+            tr.press_a_key
+        } else {
+            tr.capture_key
+        };
+        if ui
+            .add_enabled(enabled, egui::Button::new(capture))
+            .on_hover_text(tr.capture_key_tip)
+            .clicked()
+        {
+            self.capturing_hold_key = !self.capturing_hold_key;
+        }
+
+        if let Some(error) = &self.hotkey_error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        } else {
+            ui.weak((tr.hotkey_hint)(&self.toggle_hotkey));
+            ui.weak((tr.stop_hotkey_hint)(&self.stop_hotkey));
+            if !self.hold_hotkey.is_empty() {
+                ui.weak((tr.hold_hotkey_hint)(&self.hold_hotkey));
+            }
+        }
+    }
+
+    fn quick_launcher_ui(&mut self, ui: &mut egui::Ui) {
+        let tr = self.language.strings();
+        ui.label(tr.quick_launcher)
+            .on_hover_text(tr.quick_launcher_tip);
+        ui.horizontal(|ui| {
+            ui.label(tr.template);
+            if ui
+                .add(
+                    egui::TextEdit::singleline(&mut self.quick_launcher_template)
+                        .desired_width(180.0),
+                )
+                .on_hover_text(tr.template_hover)
+                .changed()
+            {
+                self.mark_config_dirty();
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label(tr.companion_port);
+            if ui
+                .add(egui::DragValue::new(&mut self.settings.listen_port))
+                .on_hover_text(tr.companion_port_tip)
+                .changed()
+            {
+                self.push_settings();
+            }
+        });
+        let Some(mut display) = vrchat::render_quick_launcher(
+            &self.quick_launcher_template,
+            self.settings.dest_ports[0],
+            self.settings.listen_port,
+        ) else {
+            ui.colored_label(ui.visuals().error_fg_color, tr.template_missing_port);
+            return;
+        };
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut display).desired_width(200.0));
+            if ui.button(tr.copy).clicked() {
+                ui.ctx().copy_text(display.clone());
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(tr.paste_launcher);
+            ui.add(
+                egui::TextEdit::singleline(&mut self.launcher_input)
+                    .hint_text("9000:localhost:9001")
+                    .desired_width(140.0),
+            )
+            .on_hover_text(tr.paste_launcher_tip);
+            if ui
+                .add_enabled(
+                    !self.checked && !self.launcher_input.trim().is_empty(),
+                    egui::Button::new(tr.apply),
+                )
+                .clicked()
+            {
+                match vrchat::parse_quick_launcher(&self.launcher_input) {
+                    Some((dest_port, listen_port)) => {
+                        self.launcher_error = false;
+                        self.settings.dest_ports[0] = dest_port;
+                        self.port_inputs[0] = dest_port.to_string();
+                        self.settings.listen_port = listen_port;
+                        self.launcher_input.clear();
+                        self.push_settings();
+                    }
+                    None => self.launcher_error = true,
+                }
+            }
+        });
+        if self.launcher_error {
+            ui.colored_label(ui.visuals().error_fg_color, tr.launcher_invalid);
+        }
+    }
+
+    fn input_mode_ui(&mut self, ui: &mut egui::Ui) {
+        let tr = self.language.strings();
+        ui.horizontal(|ui| {
+            ui.label(tr.input_mode);
+            let mut changed = false;
+            for mode in InputMode::ALL {
+                changed |= ui
+                    .radio_value(
+                        &mut self.settings.input_mode,
+                        mode,
+                        (tr.input_mode_name)(mode),
+                    )
+                    .on_hover_text(tr.input_mode_tip)
+                    .changed();
+            }
+            if changed {
+                self.push_settings();
+            }
+        });
+        // A toggle has no hold.
+        if self.settings.input_mode == InputMode::Toggle {
+            return;
+        }
+
+        if ui
+            .checkbox(&mut self.settings.randomize_hold, tr.randomize_hold)
+            .changed()
+        {
+            self.push_settings();
+        }
+
+        if self.settings.randomize_hold {
+            let min = slider_entry(ui, &mut self.settings.hold_min_ms, HOLD_RANGE, tr.min_hold)
+                .on_hover_text(tr.hold_tip);
+            let max = slider_entry(ui, &mut self.settings.hold_max_ms, HOLD_RANGE, tr.max_hold)
+                .on_hover_text(tr.hold_tip);
+            if min.changed() {
+                self.settings.hold_max_ms =
+                    self.settings.hold_max_ms.max(self.settings.hold_min_ms);
+            }
+            if max.changed() {
+                self.settings.hold_min_ms =
+                    self.settings.hold_min_ms.min(self.settings.hold_max_ms);
+            }
+            if min.changed() || max.changed() {
+                self.push_settings();
+            }
+        } else if slider_entry(ui, &mut self.settings.hold_ms, HOLD_RANGE, tr.hold_duration)
+            .on_hover_text(tr.hold_tip)
+            .changed()
+        {
+            self.push_settings();
+        }
+    }
+
+    fn interval_ui(&mut self, ui: &mut egui::Ui) {
+        let tr = self.language.strings();
+        ui.horizontal(|ui| {
+            if ui.checkbox(&mut self.interval_in_hz, tr.show_hz).changed() {
+                self.mark_config_dirty();
+            }
+            for ms in INTERVAL_PRESETS {
+                let label = if ms % 1000 == 0 {
+                    format!("{}s", ms / 1000)
+                } else {
+                    format!("{ms}ms")
+                };
+                if ui
+                    .selectable_label(self.settings.interval_ms == ms, label)
+                    .clicked()
+                {
+                    self.settings.interval_ms = ms;
+                    self.push_settings();
+                }
+            }
+        });
 
-impl OscSenderApp {
-    fn new(_: &eframe::CreationContext<'_>) -> Self {
-        let state = Arc::new(Mutex::new(AppState {
-            interval_ms: 1000,
-            hold_ms: 200,
-            is_sending: false,
-            dest_port: 9000,
-        }));
-
-        let cloned_state = state.clone();
-        thread::spawn(move || {
-            let socket = UdpSocket::bind("0.0.0.0:0").expect("Failed to bind UDP socket");
-
-            let mut prev_sending = false;
-
-            loop {
-                let (interval, hold, sending, port) = {
-                    let state = cloned_state.lock().unwrap();
-                    (
-                        state.interval_ms,
-                        state.hold_ms,
-                        state.is_sending,
-                        state.dest_port,
+        if !self.interval_in_hz {
+            if slider_entry(
+                ui,
+                &mut self.settings.interval_ms,
+                INTERVAL_RANGE,
+                tr.click_interval,
+            )
+            .on_hover_text(tr.interval_tip)
+            .changed()
+            {
+                self.push_settings();
+            }
+            return;
+        }
+
+        // Only re-derive the frequency when the interval was changed elsewhere
+        // (ms mode, presets), so rounding doesn't drift it every frame.
+        if hz_to_interval_ms(self.interval_hz) != self.settings.interval_ms {
+            self.interval_hz = 1000.0 / self.settings.interval_ms as f64;
+        }
+        let min_hz = 1000.0 / *INTERVAL_RANGE.end() as f64;
+        let max_hz = 1000.0 / *INTERVAL_RANGE.start() as f64;
+        if ui
+            .add(
+                egui::Slider::new(&mut self.interval_hz, min_hz..=max_hz)
+                    .logarithmic(true)
+                    .max_decimals(2)
+                    .text(tr.click_rate),
+            )
+            .on_hover_text(tr.interval_tip)
+            .changed()
+        {
+            self.settings.interval_ms = hz_to_interval_ms(self.interval_hz);
+            self.push_settings();
+        }
+    }
+
+    fn ramp_ui(&mut self, ui: &mut egui::Ui) {
+        let tr = self.language.strings();
+        if ui
+            .checkbox(&mut self.settings.ramp_enabled, tr.ramp)
+            .on_hover_text(tr.ramp_tip)
+            .changed()
+        {
+            self.push_settings();
+        }
+        if !self.settings.ramp_enabled {
+            return;
+        }
+
+        let start = slider_entry(
+            ui,
+            &mut self.settings.ramp_start_ms,
+            INTERVAL_RANGE,
+            tr.ramp_start,
+        );
+        let end = slider_entry(
+            ui,
+            &mut self.settings.ramp_end_ms,
+            INTERVAL_RANGE,
+            tr.ramp_end,
+        );
+        let secs = ui
+            .horizontal(|ui| {
+                ui.label(tr.ramp_duration);
+                ui.add(egui::DragValue::new(&mut self.settings.ramp_secs).range(0..=86_400))
+                    .changed()
+            })
+            .inner;
+        if start.changed() || end.changed() || secs {
+            self.push_settings();
+        }
+
+        let current = {
+            let state = self.shared.state.lock().unwrap();
+            state
+                .is_sending
+                .then(|| state.effective_settings().interval_ms)
+        };
+        if let Some(interval_ms) = current {
+            ui.label((tr.ramp_current)(interval_ms));
+        }
+    }
+
+    fn bundle_ui(&mut self, ui: &mut egui::Ui) {
+        let tr = self.language.strings();
+        let title = (tr.bundle_title)(self.settings.bundle.len());
+        egui::CollapsingHeader::new(title)
+            .id_salt("bundle")
+            .show(ui, |ui| {
+                ui.weak(tr.bundle_hint);
+                let mut changed = false;
+                ui.horizontal(|ui| {
+                    ui.label(tr.bundle_delay);
+                    changed |= ui
+                        .add(
+                            egui::DragValue::new(&mut self.settings.bundle_delay_ms)
+                                .range(0..=10_000),
+                        )
+                        .on_hover_text(tr.bundle_delay_hover)
+                        .changed();
+                });
+                let mut removed = None;
+                for (index, entry) in self.settings.bundle.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        let valid = is_valid_address(&entry.address);
+                        let text_color = (!valid).then(|| ui.visuals().weak_text_color());
+                        changed |= ui
+                            .add(
+                                egui::TextEdit::singleline(&mut entry.address)
+                                    .text_color_opt(text_color)
+                                    .desired_width(150.0),
+                            )
+                            .changed();
+                        changed |= ui
+                            .add(egui::DragValue::new(&mut entry.on_value).speed(0.1))
+                            .on_hover_text(tr.pressed_value)
+                            .changed();
+                        changed |= ui
+                            .add(egui::DragValue::new(&mut entry.off_value).speed(0.1))
+                            .on_hover_text(tr.released_value)
+                            .changed();
+                        if ui.button("✕").clicked() {
+                            removed = Some(index);
+                        }
+                    });
+                }
+                if let Some(index) = removed {
+                    self.settings.bundle.remove(index);
+                    changed = true;
+                }
+                if ui.button(tr.add_message).clicked() {
+                    self.settings.bundle.push(BundleEntry::default());
+                    changed = true;
+                }
+                if changed {
+                    self.push_settings();
+                }
+            });
+    }
+
+    fn cycle_ui(&mut self, ui: &mut egui::Ui) {
+        let tr = self.language.strings();
+        let title = (tr.cycle_title)(self.settings.cycle_addresses().len());
+        egui::CollapsingHeader::new(title)
+            .id_salt("address_cycle")
+            .show(ui, |ui| {
+                ui.weak(tr.cycle_hint);
+                let mut changed = ui
+                    .checkbox(&mut self.settings.cycle_random, tr.cycle_random)
+                    .on_hover_text(tr.cycle_random_tip)
+                    .changed();
+                if self.settings.invalid_cycle_weights() {
+                    ui.colored_label(ui.visuals().error_fg_color, tr.cycle_weights_zero);
+                }
+                let mut removed = None;
+                let mut swapped = None;
+                let count = self.settings.address_cycle.len();
+                let cycle_random = self.settings.cycle_random;
+                self.settings.cycle_weights.resize(count, 1);
+                for (index, (address, weight)) in self
+                    .settings
+                    .address_cycle
+                    .iter_mut()
+                    .zip(&mut self.settings.cycle_weights)
+                    .enumerate()
+                {
+                    ui.horizontal(|ui| {
+                        let valid = is_valid_address(address);
+                        let text_color = (!valid).then(|| ui.visuals().weak_text_color());
+                        changed |= ui
+                            .add(
+                                egui::TextEdit::singleline(address)
+                                    .text_color_opt(text_color)
+                                    .desired_width(150.0),
+                            )
+                            .changed();
+                        if cycle_random {
+                            changed |= ui
+                                .add(egui::DragValue::new(weight).range(0..=1000))
+                                .on_hover_text(tr.cycle_weight_tip)
+                                .changed();
+                        }
+                        if ui.add_enabled(index > 0, egui::Button::new("⬆")).clicked() {
+                            swapped = Some(index - 1);
+                        }
+                        if ui
+                            .add_enabled(index + 1 < count, egui::Button::new("⬇"))
+                            .clicked()
+                        {
+                            swapped = Some(index);
+                        }
+                        if ui.button("✕").clicked() {
+                            removed = Some(index);
+                        }
+                    });
+                }
+                if let Some(index) = swapped {
+                    self.settings.address_cycle.swap(index, index + 1);
+                    self.settings.cycle_weights.swap(index, index + 1);
+                    changed = true;
+                }
+                if let Some(index) = removed {
+                    self.settings.address_cycle.remove(index);
+                    self.settings.cycle_weights.remove(index);
+                    changed = true;
+                }
+                if ui.button(tr.add_address).clicked() {
+                    self.settings
+                        .address_cycle
+                        .push(self.settings.address.clone());
+                    self.settings.cycle_weights.push(1);
+                    changed = true;
+                }
+                if changed {
+                    self.push_settings();
+                }
+            });
+    }
+
+    fn sweep_ui(&mut self, ui: &mut egui::Ui) {
+        let tr = self.language.strings();
+        if ui
+            .checkbox(&mut self.settings.sweep_mode, tr.axis_sweep)
+            .on_hover_text(tr.axis_sweep_tip)
+            .changed()
+        {
+            self.settings.text_mode &= !self.settings.sweep_mode;
+            self.push_settings();
+        }
+        if !self.settings.sweep_mode {
+            return;
+        }
+
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            ui.label(tr.sweep_min);
+            changed |= ui
+                .add(egui::DragValue::new(&mut self.settings.sweep_min).speed(0.01))
+                .changed();
+            ui.label(tr.sweep_max);
+            changed |= ui
+                .add(egui::DragValue::new(&mut self.settings.sweep_max).speed(0.01))
+                .changed();
+        });
+        ui.horizontal(|ui| {
+            ui.label(tr.sweep_period);
+            changed |= ui
+                .add(
+                    egui::DragValue::new(&mut self.settings.sweep_period_ms)
+                        .range(SWEEP_PERIOD_RANGE),
+                )
+                .changed();
+        });
+        ui.horizontal(|ui| {
+            ui.label(tr.waveform);
+            for waveform in Waveform::ALL {
+                changed |= ui
+                    .radio_value(
+                        &mut self.settings.waveform,
+                        waveform,
+                        (tr.waveform_name)(waveform),
                     )
+                    .changed();
+            }
+        });
+        if changed {
+            self.push_settings();
+        }
+
+        // Two periods, so the wrap-around of the ramp and square is visible.
+        let settings = &self.settings;
+        let period = settings.sweep_period_ms.max(1);
+        let points: PlotPoints = (0..=PREVIEW_POINTS)
+            .map(|i| {
+                let ms = 2 * period * i / PREVIEW_POINTS;
+                let value = settings.sweep_value(Duration::from_millis(ms));
+                [ms as f64, f64::from(value)]
+            })
+            .collect();
+        Plot::new("waveform_preview")
+            .height(80.0)
+            .allow_drag(false)
+            .allow_zoom(false)
+            .allow_scroll(false)
+            .allow_boxed_zoom(false)
+            .show(ui, |plot| plot.line(Line::new(tr.waveform_preview, points)));
+    }
+
+    fn text_ui(&mut self, ui: &mut egui::Ui) {
+        let tr = self.language.strings();
+        if ui
+            .checkbox(&mut self.settings.text_mode, tr.text_mode)
+            .on_hover_text(tr.text_mode_tip)
+            .changed()
+        {
+            self.settings.sweep_mode &= !self.settings.text_mode;
+            self.push_settings();
+        }
+        if !self.settings.text_mode {
+            return;
+        }
+
+        let mut changed = false;
+        if self.settings.address != CHATBOX_ADDRESS && ui.button(tr.use_chatbox_address).clicked() {
+            self.settings.address = CHATBOX_ADDRESS.to_string();
+            changed = true;
+        }
+        changed |= ui
+            .add(
+                egui::TextEdit::multiline(&mut self.settings.text)
+                    .hint_text(tr.text_hint)
+                    .desired_rows(3),
+            )
+            .changed();
+        ui.horizontal(|ui| {
+            changed |= ui
+                .checkbox(&mut self.settings.text_send_bool, tr.text_send_bool)
+                .on_hover_text(tr.text_send_bool_tip)
+                .changed();
+            ui.add_enabled_ui(self.settings.text_send_bool, |ui| {
+                changed |= ui.checkbox(&mut self.settings.text_bool, "true").changed();
+            });
+        });
+        if changed {
+            self.push_settings();
+        }
+    }
+
+    fn stats_ui(&self, ui: &mut egui::Ui) {
+        let tr = self.language.strings();
+        egui::CollapsingHeader::new(tr.stats)
+            .id_salt("stats")
+            .show(ui, |ui| {
+                let stats = self.lifetime_stats();
+                ui.label((tr.total_clicks)(stats.total_clicks));
+                ui.label((tr.total_runtime)(&format_hms(stats.total_runtime_secs)));
+                let last_used = stats
+                    .last_used
+                    .and_then(|secs| Local.timestamp_opt(secs, 0).single());
+                match last_used {
+                    Some(at) => ui.label((tr.last_used)(&at.format("%Y-%m-%d %H:%M").to_string())),
+                    None => ui.label(tr.never_used),
                 };
+            });
+    }
 
-                if sending {
-                    send_click(&socket, port, hold);
-                    prev_sending = true;
+    fn send_rate_ui(&self, ui: &mut egui::Ui) {
+        let tr = self.language.strings();
+        egui::CollapsingHeader::new(tr.send_rate)
+            .id_salt("send_rate")
+            .show(ui, |ui| {
+                let points = self
+                    .shared
+                    .state
+                    .lock()
+                    .unwrap()
+                    .send_rate
+                    .points(Instant::now());
+                Plot::new("send_rate")
+                    .height(80.0)
+                    .include_x(-(RATE_WINDOW_SECS as f64))
+                    .include_x(0.0)
+                    .include_y(0.0)
+                    .allow_drag(false)
+                    .allow_zoom(false)
+                    .allow_scroll(false)
+                    .allow_boxed_zoom(false)
+                    .show(ui, |plot| {
+                        plot.line(Line::new(tr.packets_per_sec, PlotPoints::new(points)))
+                    });
+            });
+    }
 
-                    let rest_ms = interval.saturating_sub(hold).max(1);
-                    thread::sleep(Duration::from_millis(rest_ms));
-                    continue;
+    fn tap_tempo_ui(&mut self, ui: &mut egui::Ui) {
+        let tr = self.language.strings();
+        ui.horizontal(|ui| {
+            if ui.button(tr.tap).clicked() {
+                let now = Instant::now();
+                if self.taps.back().is_some_and(|&last| now - last > TAP_RESET) {
+                    self.taps.clear();
                 }
+                if self.taps.len() == TAP_HISTORY {
+                    self.taps.pop_front();
+                }
+                self.taps.push_back(now);
 
-                if prev_sending {
-                    send_value(&socket, port, 0);
+                if let (Some(first), Some(last)) = (self.taps.front(), self.taps.back())
+                    && self.taps.len() >= 2
+                {
+                    let mean = (*last - *first) / (self.taps.len() - 1) as u32;
+                    self.settings.interval_ms = (mean.as_millis() as u64)
+                        .clamp(*INTERVAL_RANGE.start(), *INTERVAL_RANGE.end());
+                    self.push_settings();
                 }
+            }
 
-                prev_sending = sending;
+            let tapping = self
+                .taps
+                .back()
+                .is_some_and(|last| last.elapsed() <= TAP_RESET);
+            if tapping && self.taps.len() >= 2 {
+                ui.label(format!(
+                    "{:.1} BPM",
+                    60_000.0 / self.settings.interval_ms as f64
+                ));
+            } else {
+                ui.weak(tr.tap_hint);
+            }
+        });
+    }
 
-                thread::sleep(Duration::from_millis(interval.max(1)));
+    fn bpm_ui(&mut self, ui: &mut egui::Ui) {
+        let tr = self.language.strings();
+        ui.horizontal(|ui| {
+            ui.label(tr.bpm);
+            let bpm = ui.add(
+                egui::DragValue::new(&mut self.settings.bpm)
+                    .range(1.0..=1000.0)
+                    .max_decimals(1),
+            );
+            let mut subdivision = false;
+            egui::ComboBox::from_id_salt("subdivision")
+                .selected_text(self.settings.subdivision.label())
+                .width(50.0)
+                .show_ui(ui, |ui| {
+                    for value in Subdivision::ALL {
+                        subdivision |= ui
+                            .selectable_value(&mut self.settings.subdivision, value, value.label())
+                            .changed();
+                    }
+                });
+            if bpm.changed() || subdivision {
+                let beat_ms = 60_000.0 / self.settings.bpm;
+                let interval = beat_ms / self.settings.subdivision.clicks_per_beat();
+                self.settings.interval_ms =
+                    (interval.round() as u64).clamp(*INTERVAL_RANGE.start(), *INTERVAL_RANGE.end());
+                self.push_settings();
             }
         });
+    }
 
-        Self {
-            interval_ms: 1000,
-            hold_ms: 200,
-            checked: false,
-            port: 9000,
-            state,
+    /// Rarely changed controls: the source interface, socket options, retry
+    /// policy and heartbeat. Whether it's expanded is saved.
+    fn advanced_ui(&mut self, ui: &mut egui::Ui) {
+        let tr = self.language.strings();
+        let response = egui::CollapsingHeader::new(tr.advanced)
+            .id_salt("advanced")
+            .open(Some(self.advanced_open))
+            .show(ui, |ui| {
+                self.bind_ui(ui);
+                let mut changed = false;
+                ui.horizontal(|ui| {
+                    ui.label(tr.send_buffer);
+                    changed |= ui
+                        .add(
+                            egui::DragValue::new(&mut self.settings.send_buffer_bytes)
+                                .range(0..=16 * 1024 * 1024),
+                        )
+                        .on_hover_text(tr.send_buffer_tip)
+                        .changed();
+                });
+                changed |= ui
+                    .checkbox(&mut self.settings.reuse_address, tr.reuse_address)
+                    .on_hover_text(tr.reuse_address_tip)
+                    .changed();
+                ui.horizontal(|ui| {
+                    ui.label(tr.send_retries);
+                    changed |= ui
+                        .add(
+                            egui::DragValue::new(&mut self.settings.send_retries)
+                                .range(0..=MAX_SEND_RETRIES),
+                        )
+                        .on_hover_text(tr.send_retries_tip)
+                        .changed();
+                    let (retries, failed) = {
+                        let state = self.shared.state.lock().unwrap();
+                        (state.retries, state.failed_sends)
+                    };
+                    ui.weak((tr.retry_stats)(retries, failed));
+                });
+                if changed {
+                    self.push_settings();
+                }
+                ui.separator();
+                self.heartbeat_ui(ui);
+            });
+        if response.header_response.clicked() {
+            self.advanced_open = !self.advanced_open;
+            self.mark_config_dirty();
         }
     }
 
-    fn nudge_port(&mut self, delta: i32) {
-        let current = self.port as i32;
-        let mut next = current + delta;
-        if next < 0 {
-            next = 0;
+    fn multicast_ui(&mut self, ui: &mut egui::Ui) {
+        let tr = self.language.strings();
+        if !self.settings.multicast {
+            return;
         }
-        if next > 65534 {
-            next = 65534;
+        if self.settings.invalid_multicast() {
+            ui.colored_label(ui.visuals().error_fg_color, tr.not_multicast);
         }
+        ui.horizontal(|ui| {
+            let mut changed = false;
+            ui.label(tr.multicast_ttl);
+            changed |= ui
+                .add(
+                    egui::DragValue::new(&mut self.settings.multicast_ttl)
+                        .range(MULTICAST_TTL_RANGE),
+                )
+                .on_hover_text(tr.multicast_ttl_tip)
+                .changed();
+            changed |= ui
+                .checkbox(&mut self.settings.multicast_loop, tr.multicast_loop)
+                .on_hover_text(tr.multicast_loop_tip)
+                .changed();
+            if changed {
+                self.push_settings();
+            }
+        });
+    }
+
+    fn bind_ui(&mut self, ui: &mut egui::Ui) {
+        let tr = self.language.strings();
+        ui.horizontal(|ui| {
+            ui.label(tr.send_from);
+            let selected = match self.settings.bind_ip {
+                Some(ip) => ip.to_string(),
+                None => tr.any_interface.to_string(),
+            };
+            let mut bind_ip = self.settings.bind_ip;
+            egui::ComboBox::from_id_salt("bind_ip")
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut bind_ip, None, tr.any_interface);
+                    for (name, ip) in &self.interfaces {
+                        ui.selectable_value(&mut bind_ip, Some(*ip), format!("{ip} ({name})"));
+                    }
+                })
+                .response
+                .on_hover_text(tr.send_from_tip);
+            if ui
+                .button("⟳")
+                .on_hover_text(tr.refresh_interfaces)
+                .clicked()
+            {
+                self.interfaces = local_interfaces();
+            }
+            if bind_ip != self.settings.bind_ip {
+                self.settings.bind_ip = bind_ip;
+                self.push_settings();
+            }
+        });
 
-        let snapped = (next & !1) as u16;
-        if snapped != self.port {
-            self.port = snapped;
-            let mut s = self.state.lock().unwrap();
-            s.dest_port = self.port;
+        let local_addr = self.shared.state.lock().unwrap().local_addr.clone();
+        match local_addr {
+            Some(Ok(addr)) => {
+                ui.weak((tr.bound_to)(&addr.to_string()));
+            }
+            Some(Err(error)) => {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+            }
+            None => {}
         }
     }
-}
 
-fn send_click(socket: &UdpSocket, port: u16, hold_ms: u64) {
-    send_value(socket, port, 1);
-    thread::sleep(Duration::from_millis(hold_ms.max(1)));
-    send_value(socket, port, 0);
-}
+    fn ports_ui(&mut self, ui: &mut egui::Ui) {
+        let tr = self.language.strings();
+        ui.label(tr.destination_ports);
 
-fn send_value(socket: &UdpSocket, port: u16, value: i32) {
-    let msg = OscMessage {
-        addr: "/input/UseRight".to_string(),
-        args: vec![OscType::Float(value as f32)],
-    };
-    if let Ok(buf) = encoder::encode(&OscPacket::Message(msg)) {
-        let addr = format!("127.0.0.1:{}", port);
-        let _ = socket.send_to(&buf, &addr);
-    }
-}
+        ui.add_enabled_ui(!self.checked, |ui| {
+            let mut removed = None;
+            for index in 0..self.settings.dest_ports.len() {
+                ui.horizontal(|ui| {
+                    if ui.button("−").on_hover_text(tr.port_down_tip).clicked() {
+                        self.nudge_port(index, -2);
+                    }
+                    let response = ui.add(
+                        egui::TextEdit::singleline(&mut self.port_inputs[index])
+                            .hint_text(tr.port_hint)
+                            .desired_width(70.0),
+                    );
+                    if response.changed()
+                        && let Ok(port) = self.port_inputs[index].trim().parse::<u32>()
+                    {
+                        self.settings.dest_ports[index] = port.min(u16::MAX as u32) as u16;
+                        self.push_settings();
+                    }
+                    if response.lost_focus() {
+                        self.port_inputs[index] = self.settings.dest_ports[index].to_string();
+                    }
+                    if ui.button("+").on_hover_text(tr.port_up_tip).clicked() {
+                        self.nudge_port(index, 2);
+                    }
 
-impl eframe::App for OscSenderApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        egui::CentralPanel::default().show(ctx, |ui| {
-            ui.heading("OSC Sender");
+                    let removable = self.settings.dest_ports.len() > 1;
+                    if ui
+                        .add_enabled(removable, egui::Button::new("✕"))
+                        .on_hover_text(tr.port_remove_tip)
+                        .clicked()
+                    {
+                        removed = Some(index);
+                    }
+                });
+            }
+
+            if let Some(index) = removed {
+                self.settings.dest_ports.remove(index);
+                self.port_inputs.remove(index);
+                self.push_settings();
+            }
+
+            if self.settings.dest_ports.len() > 1 {
+                ui.horizontal(|ui| {
+                    let mut changed = false;
+                    for mode in DestinationMode::ALL {
+                        changed |= ui
+                            .radio_value(
+                                &mut self.settings.destination_mode,
+                                mode,
+                                (tr.destination_mode_name)(mode),
+                            )
+                            .on_hover_text(tr.destination_mode_tip)
+                            .changed();
+                    }
+                    if changed {
+                        self.push_settings();
+                    }
+                });
+            }
+
+            ui.horizontal(|ui| {
+                if ui
+                    .button(tr.add_port)
+                    .on_hover_text(tr.add_port_tip)
+                    .clicked()
+                {
+                    let last = *self.settings.dest_ports.last().unwrap();
+                    let port = last.saturating_add(2);
+                    self.settings.dest_ports.push(port);
+                    self.port_inputs.push(port.to_string());
+                    self.push_settings();
+                }
+
+                if self.detect_rx.is_some() {
+                    ui.spinner();
+                } else if ui
+                    .button(tr.detect_vrchat)
+                    .on_hover_text(tr.detect_hover)
+                    .clicked()
+                {
+                    let (tx, rx) = mpsc::channel();
+                    thread::spawn(move || {
+                        let _ = tx.send(oscquery::detect_vrchat_port(DETECT_TIMEOUT));
+                    });
+                    self.detect_rx = Some(rx);
+                    self.detect_status = None;
+                }
+            });
+        });
+
+        if let Some(rx) = &self.detect_rx
+            && let Ok(result) = rx.try_recv()
+        {
+            self.detect_rx = None;
+            if let Ok(port) = result {
+                self.settings.dest_ports[0] = port;
+                self.port_inputs[0] = port.to_string();
+                self.push_settings();
+            }
+            self.detect_status = Some(result);
+        }
+        match &self.detect_status {
+            Some(Ok(port)) => {
+                ui.weak((tr.found_vrchat)(*port));
+            }
+            Some(Err(error)) => {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+            }
+            None => {}
+        }
+    }
 
+    fn listen_ui(&mut self, ui: &mut egui::Ui) {
+        let tr = self.language.strings();
+        ui.horizontal(|ui| {
+            let enabled = ui.checkbox(&mut self.settings.listen_enabled, tr.listen_on_port);
+            let port = ui.add(egui::DragValue::new(&mut self.settings.listen_port));
+            if enabled.changed() || port.changed() {
+                self.push_settings();
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label(tr.log_received);
             if ui
                 .add(
-                    egui::Slider::new(&mut self.interval_ms, 10..=2000).text("Click interval (ms)"),
+                    egui::TextEdit::singleline(&mut self.settings.listen_filter)
+                        .hint_text(tr.all_addresses_hint)
+                        .desired_width(150.0),
                 )
                 .changed()
             {
-                let mut state = self.state.lock().unwrap();
-                state.interval_ms = self.interval_ms;
+                self.push_settings();
+            }
+        });
+
+        let listen_error = self.shared.state.lock().unwrap().listen_error.clone();
+        if self.settings.listen_enabled
+            && let Some(error) = listen_error
+        {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+        self.ping_ui(ui);
+    }
+
+    fn ping_ui(&mut self, ui: &mut egui::Ui) {
+        let tr = self.language.strings();
+        let toggled = ui
+            .checkbox(&mut self.settings.ping_enabled, tr.ping)
+            .on_hover_text((tr.ping_tip)(ping::PING_ADDRESS))
+            .changed();
+        if toggled {
+            self.shared.state.lock().unwrap().ping.clear();
+            self.push_settings();
+        }
+        if !self.settings.ping_enabled {
+            return;
+        }
+        if !self.settings.listen_enabled {
+            ui.colored_label(ui.visuals().warn_fg_color, tr.ping_needs_listen);
+            return;
+        }
+        let stats = self.shared.state.lock().unwrap().ping.stats();
+        match stats {
+            Some((min, avg, max)) => ui.label((tr.ping_stats)(
+                min.as_secs_f64() * 1000.0,
+                avg.as_secs_f64() * 1000.0,
+                max.as_secs_f64() * 1000.0,
+            )),
+            None => ui.weak(tr.ping_waiting),
+        };
+    }
+
+    fn schedule_ui(&mut self, ui: &mut egui::Ui) {
+        let tr = self.language.strings();
+        let schedule = &mut self.settings.schedule;
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            changed |= ui
+                .checkbox(&mut schedule.enabled, tr.schedule)
+                .on_hover_text(tr.schedule_tip)
+                .changed();
+            changed |= minute_of_day_edit(ui, &mut schedule.start_min);
+            ui.label("–");
+            changed |= minute_of_day_edit(ui, &mut schedule.stop_min);
+        });
+        if schedule.enabled {
+            if schedule.contains(minute_of_day(Local::now())) {
+                ui.label((tr.schedule_until)(&format_minute_of_day(
+                    schedule.stop_min,
+                )));
+            } else {
+                ui.label((tr.schedule_waiting)(&format_minute_of_day(
+                    schedule.start_min,
+                )));
             }
+        }
+        if changed {
+            self.push_settings();
+        }
+    }
 
+    fn vrchat_ui(&mut self, ui: &mut egui::Ui) {
+        let tr = self.language.strings();
+        ui.horizontal(|ui| {
+            match self.shared.state.lock().unwrap().vrchat_running {
+                Some(true) => ui.colored_label(RUNNING_COLOR, tr.vrchat_running),
+                Some(false) => ui.colored_label(ui.visuals().error_fg_color, tr.vrchat_not_found),
+                None => ui.weak(tr.vrchat_checking),
+            };
             if ui
-                .add(egui::Slider::new(&mut self.hold_ms, 10..=1000).text("Hold duration (ms)"))
+                .checkbox(&mut self.settings.only_when_vrchat, tr.only_when_vrchat)
+                .on_hover_text(tr.only_when_vrchat_tip)
                 .changed()
             {
-                let mut state = self.state.lock().unwrap();
-                state.hold_ms = self.hold_ms;
+                self.push_settings();
             }
+        });
+    }
 
-            if ui.checkbox(&mut self.checked, "Send OSC").changed() {
-                let mut state = self.state.lock().unwrap();
-                state.is_sending = self.checked;
+    fn heartbeat_ui(&mut self, ui: &mut egui::Ui) {
+        let tr = self.language.strings();
+        ui.horizontal(|ui| {
+            let enabled = ui
+                .checkbox(&mut self.settings.heartbeat_enabled, tr.heartbeat)
+                .on_hover_text(tr.heartbeat_tip);
+            let interval = ui.add(
+                egui::DragValue::new(&mut self.settings.heartbeat_interval_ms)
+                    .range(HEARTBEAT_INTERVAL_RANGE)
+                    .suffix(" ms"),
+            );
+            if enabled.changed() || interval.changed() {
+                self.push_settings();
+            }
+        });
+        if !self.settings.heartbeat_enabled {
+            return;
+        }
+        ui.horizontal(|ui| {
+            ui.label(tr.heartbeat_address);
+            let valid = is_valid_address(&self.settings.heartbeat_address);
+            let text_color = (!valid).then(|| ui.visuals().weak_text_color());
+            if ui
+                .add(
+                    egui::TextEdit::singleline(&mut self.settings.heartbeat_address)
+                        .text_color_opt(text_color)
+                        .desired_width(150.0),
+                )
+                .changed()
+            {
+                self.push_settings();
             }
+        });
+    }
 
-            ui.separator();
+    fn http_ui(&mut self, ui: &mut egui::Ui) {
+        let tr = self.language.strings();
+        ui.horizontal(|ui| {
+            let enabled = ui
+                .checkbox(&mut self.settings.http_enabled, tr.http_control)
+                .on_hover_text(tr.http_control_tip);
+            let port = ui.add(egui::DragValue::new(&mut self.settings.http_port));
+            if enabled.changed() || port.changed() {
+                self.push_settings();
+            }
+        });
+        if !self.settings.http_enabled {
+            return;
+        }
 
+        let http_error = self.shared.state.lock().unwrap().http_error.clone();
+        if let Some(error) = http_error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+        for endpoint in http::ENDPOINTS {
+            let url = format!("http://127.0.0.1:{}{endpoint}", self.settings.http_port);
             ui.horizontal(|ui| {
-                ui.label("Destination Port:");
+                ui.monospace(&url);
+                ui.weak((tr.http_endpoint)(endpoint));
+            });
+        }
+    }
 
-                ui.add_enabled_ui(!self.checked, |ui| {
-                    if ui.button("−").clicked() {
-                        self.nudge_port(-2);
-                    }
-                    let mut port_str = self.port.to_string();
-                    ui.add(
-                        egui::TextEdit::singleline(&mut port_str)
-                            .hint_text("port")
-                            .interactive(false)
-                            .desired_width(70.0),
+    fn nudge_port(&mut self, index: usize, delta: i32) {
+        if let Some(next) = nudge_port(self.settings.dest_ports[index], delta) {
+            self.settings.dest_ports[index] = next;
+            self.port_inputs[index] = next.to_string();
+            self.push_settings();
+        }
+    }
+}
+
+/// Hour and minute inputs for a time stored as minutes since midnight.
+fn minute_of_day_edit(ui: &mut egui::Ui, minute: &mut u32) -> bool {
+    let (mut hour, mut min) = (*minute / 60, *minute % 60);
+    let two_digits = |n: f64, _: RangeInclusive<usize>| format!("{n:02}");
+    let hour_changed = ui
+        .add(
+            egui::DragValue::new(&mut hour)
+                .range(0..=23)
+                .custom_formatter(two_digits),
+        )
+        .changed();
+    let min_changed = ui
+        .add(
+            egui::DragValue::new(&mut min)
+                .range(0..=59)
+                .custom_formatter(two_digits),
+        )
+        .changed();
+    *minute = hour * 60 + min;
+    hour_changed || min_changed
+}
+
+fn window_level(always_on_top: bool) -> egui::WindowLevel {
+    if always_on_top {
+        egui::WindowLevel::AlwaysOnTop
+    } else {
+        egui::WindowLevel::Normal
+    }
+}
+
+/// A slider with a typeable field beside it. Typed values outside `range`
+/// are clamped into it.
+fn slider_entry<N: egui::emath::Numeric>(
+    ui: &mut egui::Ui,
+    value: &mut N,
+    range: RangeInclusive<N>,
+    text: &str,
+) -> egui::Response {
+    ui.horizontal(|ui| {
+        let slider = ui.add(egui::Slider::new(value, range.clone()).show_value(false));
+        let entry = ui.add(egui::DragValue::new(value).range(range));
+        ui.label(text);
+        slider | entry
+    })
+    .inner
+}
+
+/// A hotkey text field with an apply button, which is enabled once the text
+/// differs from `current`. Returns whether it was clicked.
+fn hotkey_row(
+    ui: &mut egui::Ui,
+    tr: &i18n::Strings,
+    label: &str,
+    input: &mut String,
+    current: &str,
+    enabled: bool,
+) -> bool {
+    ui.horizontal(|ui| {
+        ui.label(label);
+        ui.add_enabled(
+            enabled,
+            egui::TextEdit::singleline(input).desired_width(110.0),
+        );
+        let changed = input.trim() != current;
+        ui.add_enabled(enabled && changed, egui::Button::new(tr.apply))
+            .clicked()
+    })
+    .inner
+}
+
+/// Note numbers separated by commas, e.g. `60, 62`. `None` if any is not a
+/// MIDI note (0-127).
+#[cfg(feature = "midi")]
+fn parse_notes(text: &str) -> Option<Vec<u8>> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .map(|part| part.parse().ok().filter(|note| *note < 128))
+        .collect()
+}
+
+#[cfg(feature = "midi")]
+fn format_notes(notes: &[u8]) -> String {
+    notes
+        .iter()
+        .map(u8::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// A pressed key in the format the hotkey bindings are written in, e.g.
+/// `Ctrl+Shift+F`.
+fn hotkey_name(key: egui::Key, modifiers: egui::Modifiers) -> String {
+    let mut name = String::new();
+    for (held, modifier) in [
+        (modifiers.ctrl, "Ctrl+"),
+        (modifiers.alt, "Alt+"),
+        (modifiers.shift, "Shift+"),
+    ] {
+        if held {
+            name.push_str(modifier);
+        }
+    }
+    name.push_str(key.name());
+    name
+}
+
+/// What the destination field shows: the hostname, if one is set.
+fn dest_input(settings: &Settings) -> String {
+    if settings.dest_host.is_empty() {
+        settings.dest_ip.to_string()
+    } else {
+        settings.dest_host.clone()
+    }
+}
+
+/// Adds what a tab has sent since it was spawned to `stats`.
+fn add_tab_stats(stats: &mut config::Stats, state: &AppState) {
+    stats.total_clicks += state.lifetime_clicks;
+    stats.total_runtime_secs += state.lifetime_runtime().as_secs();
+}
+
+/// A duration as H:MM:SS.
+fn format_hms(secs: u64) -> String {
+    format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+/// A byte count in B, KB or MB, for estimates.
+fn format_bytes(bytes: u64) -> String {
+    match bytes {
+        0..1_000 => format!("{bytes} B"),
+        1_000..1_000_000 => format!("{:.1} KB", bytes as f64 / 1e3),
+        _ => format!("{:.1} MB", bytes as f64 / 1e6),
+    }
+}
+
+fn hz_to_interval_ms(hz: f64) -> u64 {
+    ((1000.0 / hz).round() as u64).clamp(*INTERVAL_RANGE.start(), *INTERVAL_RANGE.end())
+}
+
+fn port_inputs(settings: &Settings) -> Vec<String> {
+    settings.dest_ports.iter().map(u16::to_string).collect()
+}
+
+/// Draws a dot that flashes bright for [`FLASH_DURATION`] after each send.
+fn send_indicator(ui: &mut egui::Ui, last_sent: Option<Instant>) {
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(12.0, 12.0), egui::Sense::hover());
+    let intensity = last_sent.map_or(0.0, |at| {
+        1.0 - (at.elapsed().as_secs_f32() / FLASH_DURATION.as_secs_f32()).min(1.0)
+    });
+    let idle = ui.visuals().widgets.inactive.bg_fill;
+    let color = idle.lerp_to_gamma(egui::Color32::from_rgb(80, 255, 120), intensity);
+    ui.painter()
+        .circle_filled(rect.center(), rect.width() / 2.0, color);
+}
+
+impl eframe::App for OscSenderApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        // The sender thread can stop on its own (e.g. click limit reached).
+        self.checked = self.shared.state.lock().unwrap().is_sending;
+
+        let focused = ctx.input(|i| i.focused);
+        for tab in &self.tabs {
+            let (enabled, paused) = {
+                let state = tab.shared.state.lock().unwrap();
+                (state.settings.pause_when_unfocused, state.unfocused)
+            };
+            let unfocused = enabled && !focused;
+            if unfocused != paused {
+                tab.shared.update(|s| s.set_unfocused(unfocused));
+            }
+        }
+
+        let stopped_at = self
+            .tabs
+            .iter()
+            .filter_map(|tab| tab.shared.state.lock().unwrap().stopped_at)
+            .max();
+        if stopped_at.is_some_and(|at| at.elapsed() < STOP_BANNER_DURATION) {
+            let tr = self.language.strings();
+            egui::TopBottomPanel::top("stopped").show(ctx, |ui| {
+                ui.vertical_centered(|ui| {
+                    ui.heading(
+                        egui::RichText::new(tr.stopped_banner)
+                            .strong()
+                            .color(ui.visuals().error_fg_color),
                     );
-                    if ui.button("+").clicked() {
-                        self.nudge_port(2);
-                    }
                 });
             });
+        }
 
-            let mut display = format!("{}:localhost:{}", self.port, (self.port as u32) + 1);
-            ui.label("Quick Launcher OSC setting value");
-            if ui
-                .add(egui::TextEdit::singleline(&mut display).desired_width(220.0))
-                .changed()
-            {}
-        });
+        if self.compact {
+            egui::CentralPanel::default().show(ctx, |ui| self.compact_ui(ui));
+        } else {
+            egui::TopBottomPanel::bottom("status").show(ctx, |ui| self.status_ui(ui));
+            egui::CentralPanel::default().show(ctx, |ui| {
+                egui::ScrollArea::vertical().show(ui, |ui| self.main_ui(ui));
+            });
+        }
+        self.confirm_send_ui(ctx);
+        self.confirm_broadcast_ui(ctx);
+        self.confirm_reset_ui(ctx);
+
+        #[cfg(feature = "tray")]
+        if let Some(tray) = &self.tray
+            && self.close_to_tray
+            && !tray.is_quitting()
+            && ctx.input(|i| i.viewport().close_requested())
+        {
+            ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+            tray.hide_window(ctx);
+        }
+
+        if self
+            .tabs
+            .iter()
+            .any(|tab| tab.shared.state.lock().unwrap().is_sending)
+        {
+            self.stats.last_used = Some(Local::now().timestamp());
+            if self.stats_saved_at.elapsed() >= STATS_SAVE_INTERVAL {
+                self.mark_config_dirty();
+            }
+        }
+
+        if self
+            .config_dirty_since
+            .is_some_and(|since| since.elapsed() >= CONFIG_SAVE_DELAY)
+        {
+            self.save_config();
+        }
 
         ctx.request_repaint_after(Duration::from_millis(16));
     }
+
+    /// Only the window geometry is persisted by eframe; everything else lives
+    /// in the config file.
+    fn persist_egui_memory(&self) -> bool {
+        false
+    }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        if self.config_dirty_since.is_some() || self.lifetime_stats() != self.saved_stats {
+            self.save_config();
+        }
+
+        self.hotkeys = None;
+        #[cfg(feature = "tray")]
+        {
+            self.tray = None;
+        }
+
+        // Signal every tab before joining any, so they wind down together.
+        for tab in &self.tabs {
+            tab.shared.request_shutdown();
+        }
+        for tab in self.tabs.drain(..) {
+            tab.shut_down();
+        }
+    }
+}
+
+/// Runs the sender loop on the current thread without a window, starting
+/// immediately and stopping (with a final release) on Ctrl-C.
+fn run_headless(cli: &cli::Cli) {
+    let config = config::load();
+    let mut settings = config.settings;
+    cli.apply(&mut settings);
+
+    // There's no window to confirm in, so only trusted destinations start.
+    let shared = Arc::new(Shared::new(settings));
+    let mut untrusted = None;
+    shared.update(|s| {
+        s.trusted_ips = config.trusted_ips;
+        s.trusted_hosts = config.trusted_hosts;
+        if !s.request_start() {
+            untrusted = s.confirm_start.take();
+        }
+    });
+    if let Some(dest) = untrusted {
+        eprintln!(
+            "{dest} is not this computer and not trusted yet. Start sending to it once in \
+             the window with \"Don't ask again\" checked, then run headless again."
+        );
+        std::process::exit(1);
+    }
+
+    let handler_shared = shared.clone();
+    if let Err(e) = ctrlc::set_handler(move || handler_shared.request_shutdown()) {
+        eprintln!("Failed to install Ctrl-C handler: {e}");
+    }
+
+    let heartbeat_shared = shared.clone();
+    thread::spawn(move || run_heartbeat(&heartbeat_shared));
+    let watch_shared = shared.clone();
+    thread::spawn(move || vrchat::run_vrchat_watch(&watch_shared));
+    let resolver_shared = shared.clone();
+    thread::spawn(move || resolve::run_resolver(&resolver_shared));
+
+    run_sender(&shared);
 }
 
 fn main() {
+    let cli = cli::Cli::parse();
+
+    if cli.headless {
+        run_headless(&cli);
+        return;
+    }
+
+    let config = config::load();
+    let scale = config.ui_scale;
+
     let options = eframe::NativeOptions {
-        viewport: egui::ViewportBuilder::default().with_inner_size([280.0, 160.0]),
+        viewport: egui::ViewportBuilder::default()
+            .with_inner_size(WINDOW_SIZE * scale)
+            .with_window_level(window_level(config.always_on_top)),
+        persistence_path: config::window_state_path(),
         ..Default::default()
     };
 
     eframe::run_native(
         "OSC Sender",
         options,
-        Box::new(|cc| Ok(Box::new(OscSenderApp::new(cc)) as Box<dyn eframe::App>)),
+        Box::new(|cc| Ok(Box::new(OscSenderApp::new(cc, config, &cli)) as Box<dyn eframe::App>)),
     )
     .unwrap();
 }
@@ -0,0 +1,80 @@
+//! MIDI controller input, so notes can trigger clicks.
+
+use std::sync::Arc;
+
+use midir::{MidiInput, MidiInputConnection};
+use osc_sender::osc_core::Shared;
+
+const CLIENT_NAME: &str = "OSC Sender";
+const NOTE_OFF: u8 = 0x80;
+const NOTE_ON: u8 = 0x90;
+
+/// An open input device. Dropping it closes the connection.
+pub struct Midi {
+    _connection: MidiInputConnection<()>,
+}
+
+impl Midi {
+    /// Opens the input device called `device`. A Note-On of one of `notes`
+    /// (any note when empty) clicks; with `note_off_release` it only presses,
+    /// and the matching Note-Off releases.
+    pub fn connect(
+        device: &str,
+        shared: Arc<Shared>,
+        notes: Vec<u8>,
+        note_off_release: bool,
+    ) -> Result<Self, String> {
+        let input = MidiInput::new(CLIENT_NAME).map_err(|e| format!("MIDI unavailable: {e}"))?;
+        let port = input
+            .ports()
+            .into_iter()
+            .find(|port| input.port_name(port).is_ok_and(|name| name == device))
+            .ok_or_else(|| format!("MIDI device \"{device}\" not found"))?;
+        let connection = input
+            .connect(
+                &port,
+                "osc-sender-input",
+                move |_, message, _| {
+                    let Some((note, on)) = note_event(message) else {
+                        return;
+                    };
+                    if !notes.is_empty() && !notes.contains(&note) {
+                        return;
+                    }
+                    if note_off_release {
+                        shared.send_now(on);
+                    } else if on {
+                        shared.request_click();
+                    }
+                },
+                (),
+            )
+            .map_err(|e| format!("Failed to open MIDI device \"{device}\": {e}"))?;
+        Ok(Self {
+            _connection: connection,
+        })
+    }
+}
+
+/// Names of the connected input devices.
+pub fn devices() -> Result<Vec<String>, String> {
+    let input = MidiInput::new(CLIENT_NAME).map_err(|e| format!("MIDI unavailable: {e}"))?;
+    Ok(input
+        .ports()
+        .iter()
+        .filter_map(|port| input.port_name(port).ok())
+        .collect())
+}
+
+/// The note of a Note-On or Note-Off message, and whether it is on. A Note-On
+/// with velocity 0 counts as off, as many controllers send it that way.
+fn note_event(message: &[u8]) -> Option<(u8, bool)> {
+    let [status, note, velocity] = *message else {
+        return None;
+    };
+    match status & 0xf0 {
+        NOTE_ON => Some((note, velocity > 0)),
+        NOTE_OFF => Some((note, false)),
+        _ => None,
+    }
+}
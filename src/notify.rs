@@ -0,0 +1,24 @@
+//! Desktop notifications for unattended runs: when sending starts and when
+//! it stops on its own.
+
+use std::thread;
+
+use notify_rust::Notification;
+
+use crate::osc_core::Settings;
+
+/// Shows `body` as a notification when `settings.notify` is on. Showing one
+/// can wait on the OS notification service, so it happens on its own thread,
+/// and a failure is ignored rather than affecting sending.
+pub(crate) fn notify(settings: &Settings, body: &'static str) {
+    if !settings.notify {
+        return;
+    }
+    thread::spawn(move || {
+        let _ = Notification::new()
+            .appname("OSC Sender")
+            .summary("OSC Sender")
+            .body(body)
+            .show();
+    });
+}
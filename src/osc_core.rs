@@ -0,0 +1,3177 @@
+//! The sender data model, the worker loop and OSC encoding, with no GUI
+//! dependency.
+
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::fs;
+use std::io::{self, Write};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, UdpSocket};
+use std::ops::RangeInclusive;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use chrono::{DateTime, Local, SecondsFormat, Timelike};
+use rand::distr::weighted::WeightedIndex;
+use rand_distr::{Distribution, Normal};
+use rosc::{OscBundle, OscMessage, OscPacket, OscTime, OscType, encoder};
+use serde::{Deserialize, Serialize};
+use socket2::{Domain, Protocol, Socket, Type};
+
+use crate::notify::notify;
+use crate::ping::Ping;
+use crate::script::{Recording, Script, play_script};
+
+pub const DEFAULT_ADDRESS: &str = "/input/UseRight";
+/// Where VRChat reads chatbox text from.
+pub const CHATBOX_ADDRESS: &str = "/chatbox/input";
+const DEFAULT_IP: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+pub(crate) const BIND_RETRY_INTERVAL: Duration = Duration::from_secs(2);
+pub const INTERVAL_RANGE: RangeInclusive<u64> = 10..=2000;
+pub const LOG_CAPACITY: usize = 200;
+/// Seconds of send rate kept for the graph.
+pub const RATE_WINDOW_SECS: u64 = 30;
+const SCHEDULE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// A send blocked longer than this fails instead of stalling the loop.
+const SEND_TIMEOUT: Duration = Duration::from_millis(100);
+/// The click socket is rebound after this many sends fail in a row.
+pub const MAX_SEND_FAILURES: u32 = 5;
+/// Wait before the first retry of a failed send, doubled for each next one.
+const RETRY_BACKOFF: Duration = Duration::from_millis(5);
+const MAX_RETRY_BACKOFF: Duration = Duration::from_millis(100);
+pub const MAX_SEND_RETRIES: u32 = 5;
+/// The special OSC timetag meaning "apply on receipt".
+const OSC_IMMEDIATELY: OscTime = OscTime {
+    seconds: 0,
+    fractional: 1,
+};
+
+/// User-editable sender configuration. This is what gets persisted to disk.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub interval_ms: u64,
+    pub input_mode: InputMode,
+    pub hold_ms: u64,
+    /// Hold each click for a random time in `hold_min_ms..=hold_max_ms`
+    /// instead of `hold_ms`.
+    pub randomize_hold: bool,
+    pub hold_min_ms: u64,
+    pub hold_max_ms: u64,
+    /// Every click is sent to each of these ports, or to one after another
+    /// per [`Settings::destination_mode`]. Never empty.
+    pub dest_ports: Vec<u16>,
+    pub destination_mode: DestinationMode,
+    pub dest_ip: IpAddr,
+    /// Hostname that [`run_resolver`](crate::resolve::run_resolver) keeps
+    /// resolving into `dest_ip`; empty sends to `dest_ip` as entered.
+    pub dest_host: String,
+    /// Send to `dest_ip` as an IPv4 multicast group, with the TTL and
+    /// loopback options below.
+    pub multicast: bool,
+    /// Router hops a multicast packet may cross; 1 keeps it on the local
+    /// network.
+    pub multicast_ttl: u32,
+    /// Deliver multicast packets to listeners on this machine too.
+    pub multicast_loop: bool,
+    /// Set `SO_BROADCAST`, so `dest_ip` may be 255.255.255.255 or a subnet
+    /// broadcast address. Never persisted, so broadcasting is always
+    /// confirmed again after a restart.
+    #[serde(skip)]
+    pub broadcast: bool,
+    pub address: String,
+    /// Releases go here instead of `address`, for mappings with paired
+    /// set/reset addresses. Empty (or invalid) releases on `address`.
+    pub release_address: String,
+    pub arg_kind: ArgKind,
+    pub on_value: f32,
+    pub off_value: f32,
+    pub randomize_interval: bool,
+    /// How randomized intervals are drawn.
+    pub jitter: Jitter,
+    pub interval_min_ms: u64,
+    pub interval_max_ms: u64,
+    /// Standard deviation of [`Jitter::Gaussian`] intervals around
+    /// `interval_ms`.
+    pub jitter_std_dev_ms: u64,
+    /// Move `interval_ms` linearly from `ramp_start_ms` to `ramp_end_ms`
+    /// over the first `ramp_secs` of a session, then hold it there.
+    pub ramp_enabled: bool,
+    pub ramp_start_ms: u64,
+    pub ramp_end_ms: u64,
+    pub ramp_secs: u64,
+    pub burst_mode: bool,
+    pub burst_count: u32,
+    pub burst_pause_ms: u64,
+    /// Stop automatically after this many clicks; 0 means no limit.
+    pub click_limit: u32,
+    /// Stop automatically after this many seconds; 0 means no limit.
+    pub max_runtime_secs: u64,
+    /// Wait this long after sending is turned on before the first click.
+    pub start_delay_ms: u64,
+    /// When any entry is valid, successive clicks go to these addresses in
+    /// turn instead of `address`.
+    pub address_cycle: Vec<String>,
+    /// Pick each click's `address_cycle` entry at random by weight instead
+    /// of in turn.
+    pub cycle_random: bool,
+    /// Relative chance of the `address_cycle` entry at the same index being
+    /// picked. Entries without a weight count as 1.
+    pub cycle_weights: Vec<u32>,
+    /// Extra messages sent together with `address` in one bundle per press
+    /// and release, for parameters that must change at the same time.
+    pub bundle: Vec<BundleEntry>,
+    /// Timetag bundles this far in the future so receivers that honor OSC
+    /// scheduling apply them later; 0 means immediately.
+    pub bundle_delay_ms: u64,
+    /// Tempo for the BPM input. Only drives `interval_ms` when edited.
+    pub bpm: f64,
+    pub subdivision: Subdivision,
+    /// Listen for incoming OSC (e.g. VRChat's parameter output) and log it.
+    pub listen_enabled: bool,
+    pub listen_port: u16,
+    /// Only received addresses starting with this are logged; empty logs all.
+    pub listen_filter: String,
+    /// Serve the HTTP control endpoints on localhost at `http_port`.
+    pub http_enabled: bool,
+    pub http_port: u16,
+    /// Ping the destinations once a second and time the echoes that come
+    /// back to `listen_port`.
+    pub ping_enabled: bool,
+    /// Instead of clicking, send a float on `address` every interval that
+    /// sweeps between `sweep_min` and `sweep_max`, for axis inputs such as
+    /// `/input/Vertical`.
+    pub sweep_mode: bool,
+    pub sweep_min: f32,
+    pub sweep_max: f32,
+    /// Length of one full waveform cycle.
+    pub sweep_period_ms: u64,
+    pub waveform: Waveform,
+    /// Instead of clicking, send `text` as a string on `address` every
+    /// interval, e.g. to VRChat's [`CHATBOX_ADDRESS`].
+    pub text_mode: bool,
+    pub text: String,
+    /// Follow the string with `text_bool`; the chatbox reads it as "send
+    /// immediately" rather than opening the keyboard.
+    pub text_send_bool: bool,
+    pub text_bool: bool,
+    /// Append [`AppState::sequence`] as an extra `Int` argument to every
+    /// sent message, so receivers can spot dropped packets.
+    pub append_sequence: bool,
+    /// Send `true` to `heartbeat_address` every `heartbeat_interval_ms`, on
+    /// its own thread, whether or not sending is on or paused.
+    pub heartbeat_enabled: bool,
+    pub heartbeat_address: String,
+    pub heartbeat_interval_ms: u64,
+    /// Clicks are spaced out so the sender never exceeds this many packets
+    /// per second; 0 means no cap.
+    pub max_packets_per_sec: u32,
+    /// Pause while the app window isn't focused, resuming on refocus.
+    pub pause_when_unfocused: bool,
+    /// Show a desktop notification when sending starts, and when a limit,
+    /// the max runtime, the schedule or a script pass stops it.
+    pub notify: bool,
+    /// Pause while no VRChat process is found.
+    pub only_when_vrchat: bool,
+    /// Local address to send from, so packets leave through that interface;
+    /// `None` lets the OS pick one of the destination's address family.
+    pub bind_ip: Option<IpAddr>,
+    /// Times a failed send is retried, with a doubling backoff, before it
+    /// counts as failed.
+    pub send_retries: u32,
+    /// `SO_SNDBUF` for the send socket; 0 keeps the OS default.
+    pub send_buffer_bytes: u32,
+    /// Set `SO_REUSEADDR` on the send socket.
+    pub reuse_address: bool,
+    pub schedule: Schedule,
+    /// Start the loaded script over when it ends instead of stopping.
+    pub script_loop: bool,
+    /// Log and count sends without touching the network. Never persisted, so
+    /// a restart always sends for real.
+    #[serde(skip)]
+    pub dry_run: bool,
+}
+
+/// OSC argument type used for the pressed/released values.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ArgKind {
+    Int,
+    #[default]
+    Float,
+    Bool,
+}
+
+impl ArgKind {
+    pub const ALL: [ArgKind; 3] = [ArgKind::Int, ArgKind::Float, ArgKind::Bool];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ArgKind::Int => "Int",
+            ArgKind::Float => "Float",
+            ArgKind::Bool => "Bool",
+        }
+    }
+
+    /// Converts a configured value to this type. Bool treats any non-zero
+    /// value as `true`.
+    pub fn arg(self, value: f32) -> OscType {
+        match self {
+            ArgKind::Int => OscType::Int(value.round() as i32),
+            ArgKind::Float => OscType::Float(value),
+            ArgKind::Bool => OscType::Bool(value != 0.0),
+        }
+    }
+}
+
+/// One extra message in the click bundle. Uses the same argument type as the
+/// main address.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BundleEntry {
+    pub address: String,
+    pub on_value: f32,
+    pub off_value: f32,
+}
+
+impl Default for BundleEntry {
+    fn default() -> Self {
+        Self {
+            address: "/avatar/parameters/".to_string(),
+            on_value: 1.0,
+            off_value: 0.0,
+        }
+    }
+}
+
+/// Note length of one click when the interval is set from a BPM, with the
+/// beat being a quarter note.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Subdivision {
+    Half,
+    #[default]
+    Quarter,
+    Eighth,
+    Sixteenth,
+}
+
+impl Subdivision {
+    pub const ALL: [Subdivision; 4] = [
+        Subdivision::Half,
+        Subdivision::Quarter,
+        Subdivision::Eighth,
+        Subdivision::Sixteenth,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Subdivision::Half => "1/2",
+            Subdivision::Quarter => "1/4",
+            Subdivision::Eighth => "1/8",
+            Subdivision::Sixteenth => "1/16",
+        }
+    }
+
+    pub fn clicks_per_beat(self) -> f64 {
+        match self {
+            Subdivision::Half => 0.5,
+            Subdivision::Quarter => 1.0,
+            Subdivision::Eighth => 2.0,
+            Subdivision::Sixteenth => 4.0,
+        }
+    }
+}
+
+/// How one click drives the input.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InputMode {
+    /// Press, then release after the hold time.
+    #[default]
+    Momentary,
+    /// Flip between pressed and released, never releasing on its own.
+    Toggle,
+}
+
+impl InputMode {
+    pub const ALL: [InputMode; 2] = [InputMode::Momentary, InputMode::Toggle];
+}
+
+/// How clicks are spread over several destination ports.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DestinationMode {
+    /// Every click goes to every port.
+    #[default]
+    Broadcast,
+    /// Each click goes to the next port in turn.
+    RoundRobin,
+}
+
+impl DestinationMode {
+    pub const ALL: [DestinationMode; 2] = [DestinationMode::Broadcast, DestinationMode::RoundRobin];
+}
+
+/// Distribution of randomized intervals.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Jitter {
+    /// Evenly between `interval_min_ms` and `interval_max_ms`.
+    #[default]
+    Uniform,
+    /// Normally around `interval_ms`, cut off at three standard deviations.
+    Gaussian,
+}
+
+impl Jitter {
+    pub const ALL: [Jitter; 2] = [Jitter::Uniform, Jitter::Gaussian];
+}
+
+/// Shape of the value sent in sweep mode over one period.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Waveform {
+    /// Up from the minimum to the maximum and back down.
+    #[default]
+    Triangle,
+    /// Up from the minimum to the maximum, then jump back.
+    Ramp,
+    /// Smoothly from the minimum to the maximum and back.
+    Sine,
+    /// The minimum for the first half of the period, then the maximum.
+    Square,
+}
+
+impl Waveform {
+    pub const ALL: [Waveform; 4] = [
+        Waveform::Triangle,
+        Waveform::Ramp,
+        Waveform::Sine,
+        Waveform::Square,
+    ];
+
+    /// Position within the sweep bounds, from 0 to 1, at `phase` (0 to 1)
+    /// through the period.
+    pub fn sample(self, phase: f32) -> f32 {
+        match self {
+            Waveform::Triangle => 1.0 - (2.0 * phase - 1.0).abs(),
+            Waveform::Ramp => phase,
+            Waveform::Sine => 0.5 - 0.5 * (std::f32::consts::TAU * phase).cos(),
+            Waveform::Square => {
+                if phase < 0.5 {
+                    0.0
+                } else {
+                    1.0
+                }
+            }
+        }
+    }
+}
+
+/// Daily time window in which sending is turned on automatically. Times are
+/// minutes since local midnight; a start after the stop spans midnight.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Schedule {
+    pub enabled: bool,
+    pub start_min: u32,
+    pub stop_min: u32,
+}
+
+impl Default for Schedule {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            start_min: 20 * 60,
+            stop_min: 20 * 60 + 30,
+        }
+    }
+}
+
+impl Schedule {
+    /// Whether `minute` (since midnight) falls in the window. Equal start and
+    /// stop times make an empty window.
+    pub fn contains(&self, minute: u32) -> bool {
+        if self.start_min <= self.stop_min {
+            (self.start_min..self.stop_min).contains(&minute)
+        } else {
+            minute >= self.start_min || minute < self.stop_min
+        }
+    }
+}
+
+/// Minutes since local midnight, for comparing against a [`Schedule`].
+pub fn minute_of_day(time: DateTime<Local>) -> u32 {
+    time.hour() * 60 + time.minute()
+}
+
+/// Formats minutes since midnight as `HH:MM`.
+pub fn format_minute_of_day(minute: u32) -> String {
+    format!("{:02}:{:02}", minute / 60, minute % 60)
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            interval_ms: 1000,
+            input_mode: InputMode::default(),
+            hold_ms: 200,
+            randomize_hold: false,
+            hold_min_ms: 150,
+            hold_max_ms: 250,
+            dest_ports: vec![9000],
+            destination_mode: DestinationMode::default(),
+            dest_ip: DEFAULT_IP,
+            dest_host: String::new(),
+            multicast: false,
+            multicast_ttl: 1,
+            multicast_loop: true,
+            broadcast: false,
+            address: DEFAULT_ADDRESS.to_string(),
+            release_address: String::new(),
+            arg_kind: ArgKind::default(),
+            on_value: 1.0,
+            off_value: 0.0,
+            randomize_interval: false,
+            jitter: Jitter::default(),
+            interval_min_ms: 800,
+            interval_max_ms: 1200,
+            jitter_std_dev_ms: 50,
+            ramp_enabled: false,
+            ramp_start_ms: 1000,
+            ramp_end_ms: 200,
+            ramp_secs: 60,
+            burst_mode: false,
+            burst_count: 5,
+            burst_pause_ms: 2000,
+            click_limit: 0,
+            max_runtime_secs: 0,
+            start_delay_ms: 0,
+            address_cycle: Vec::new(),
+            cycle_random: false,
+            cycle_weights: Vec::new(),
+            bundle: Vec::new(),
+            bundle_delay_ms: 0,
+            bpm: 120.0,
+            subdivision: Subdivision::default(),
+            listen_enabled: false,
+            ping_enabled: false,
+            listen_port: 9001,
+            http_enabled: false,
+            http_port: 9050,
+            listen_filter: "/avatar/parameters/".to_string(),
+            sweep_mode: false,
+            sweep_min: -1.0,
+            sweep_max: 1.0,
+            sweep_period_ms: 4000,
+            waveform: Waveform::default(),
+            text_mode: false,
+            text: String::new(),
+            text_send_bool: true,
+            text_bool: true,
+            append_sequence: false,
+            heartbeat_enabled: false,
+            heartbeat_address: "/avatar/parameters/alive".to_string(),
+            heartbeat_interval_ms: 5000,
+            max_packets_per_sec: 100,
+            pause_when_unfocused: false,
+            notify: false,
+            only_when_vrchat: false,
+            bind_ip: None,
+            send_retries: 0,
+            send_buffer_bytes: 0,
+            reuse_address: false,
+            schedule: Schedule::default(),
+            script_loop: false,
+            dry_run: false,
+        }
+    }
+}
+
+impl Settings {
+    pub fn destinations(&self) -> Vec<SocketAddr> {
+        self.dest_ports
+            .iter()
+            .map(|&port| SocketAddr::new(self.dest_ip, port))
+            .collect()
+    }
+
+    /// The address the send socket binds to: `bind_ip`, or the unspecified
+    /// address of the destination's family, since an IPv4 socket can't
+    /// reach an IPv6 destination.
+    pub fn local_bind_ip(&self) -> IpAddr {
+        self.bind_ip.unwrap_or(match self.dest_ip {
+            IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+        })
+    }
+
+    /// Multicast is on but `dest_ip` isn't in 224.0.0.0/4, so the multicast
+    /// options aren't applied.
+    pub fn invalid_multicast(&self) -> bool {
+        self.multicast && !matches!(self.dest_ip, IpAddr::V4(ip) if ip.is_multicast())
+    }
+
+    pub fn socket_config(&self) -> SocketConfig {
+        SocketConfig {
+            bind_ip: self.local_bind_ip(),
+            send_buffer_bytes: self.send_buffer_bytes,
+            reuse_address: self.reuse_address,
+            multicast: (self.multicast && !self.invalid_multicast())
+                .then_some((self.multicast_ttl, self.multicast_loop)),
+            broadcast: self.broadcast && self.dest_ip.is_ipv4(),
+        }
+    }
+
+    /// What one press or release sends: the main address, then every bundle
+    /// entry whose address is valid.
+    pub fn click_packet(&self, pressed: bool) -> OscPacket {
+        let value = |on, off| self.arg_kind.arg(if pressed { on } else { off });
+        let main = OscMessage {
+            addr: if pressed {
+                self.address.clone()
+            } else {
+                self.release_address().to_string()
+            },
+            args: vec![value(self.on_value, self.off_value)],
+        };
+        let extra = self
+            .bundle
+            .iter()
+            .filter(|entry| is_valid_address(&entry.address))
+            .map(|entry| OscMessage {
+                addr: entry.address.clone(),
+                args: vec![value(entry.on_value, entry.off_value)],
+            });
+        build_packet(
+            std::iter::once(main).chain(extra).collect(),
+            Duration::from_millis(self.bundle_delay_ms),
+        )
+    }
+
+    /// Where releases go: `release_address` when it's valid, else the
+    /// press address.
+    pub fn release_address(&self) -> &str {
+        if is_valid_address(&self.release_address) {
+            &self.release_address
+        } else {
+            &self.address
+        }
+    }
+
+    /// The sweep value `elapsed` after sending started.
+    pub fn sweep_value(&self, elapsed: Duration) -> f32 {
+        let period = self.sweep_period_ms.max(1) as f32;
+        let phase = (elapsed.as_millis() as f32 % period) / period;
+        self.sweep_min + (self.sweep_max - self.sweep_min) * self.waveform.sample(phase)
+    }
+
+    /// One sweep update. Always a float, whatever `arg_kind` is.
+    pub fn sweep_packet(&self, elapsed: Duration) -> OscPacket {
+        let message = OscMessage {
+            addr: self.address.clone(),
+            args: vec![OscType::Float(self.sweep_value(elapsed))],
+        };
+        build_packet(vec![message], Duration::from_millis(self.bundle_delay_ms))
+    }
+
+    /// What a sweep ends on: the off value as a float, on the sweep address.
+    pub fn sweep_end_packet(&self) -> OscPacket {
+        let message = OscMessage {
+            addr: self.address.clone(),
+            args: vec![OscType::Float(self.off_value)],
+        };
+        build_packet(vec![message], Duration::from_millis(self.bundle_delay_ms))
+    }
+
+    /// One text message: the string, then the bool when `text_send_bool`.
+    pub fn text_packet(&self) -> OscPacket {
+        let mut args = vec![OscType::String(self.text.clone())];
+        if self.text_send_bool {
+            args.push(OscType::Bool(self.text_bool));
+        }
+        let message = OscMessage {
+            addr: self.address.clone(),
+            args,
+        };
+        build_packet(vec![message], Duration::from_millis(self.bundle_delay_ms))
+    }
+
+    /// Whether every interval sends a single message (a sweep update or a
+    /// text) instead of a press and release.
+    pub fn sends_single_message(&self) -> bool {
+        self.sweep_mode || self.text_mode
+    }
+
+    /// The valid entries of `address_cycle`, in order.
+    pub fn cycle_addresses(&self) -> Vec<&str> {
+        self.cycle_pool()
+            .into_iter()
+            .map(|(address, _)| address)
+            .collect()
+    }
+
+    /// The valid entries of `address_cycle` with their weights, in order.
+    pub fn cycle_pool(&self) -> Vec<(&str, u32)> {
+        self.address_cycle
+            .iter()
+            .enumerate()
+            .filter(|(_, address)| is_valid_address(address))
+            .map(|(index, address)| (address.as_str(), self.cycle_weight(index)))
+            .collect()
+    }
+
+    pub fn cycle_weight(&self, index: usize) -> u32 {
+        self.cycle_weights.get(index).copied().unwrap_or(1)
+    }
+
+    /// Random picking is on but every valid address weighs 0, so clicks go
+    /// through the cycle in turn instead.
+    pub fn invalid_cycle_weights(&self) -> bool {
+        let pool = self.cycle_pool();
+        self.cycle_random && !pool.is_empty() && pool.iter().all(|&(_, weight)| weight == 0)
+    }
+
+    /// Packets one click (or sweep update or text) sends across all
+    /// destinations.
+    pub fn packets_per_click(&self) -> u64 {
+        self.click_packets().len() as u64 * self.dests_per_click()
+    }
+
+    /// What one click sends to each of its destinations: a press and a
+    /// release, or a single message for toggles, sweeps and texts.
+    fn click_packets(&self) -> Vec<OscPacket> {
+        if self.sweep_mode {
+            vec![self.sweep_packet(Duration::ZERO)]
+        } else if self.text_mode {
+            vec![self.text_packet()]
+        } else if self.input_mode == InputMode::Toggle {
+            vec![self.click_packet(true)]
+        } else {
+            vec![self.click_packet(true), self.click_packet(false)]
+        }
+    }
+
+    fn dests_per_click(&self) -> u64 {
+        match self.destination_mode {
+            DestinationMode::Broadcast => self.dest_ports.len() as u64,
+            DestinationMode::RoundRobin => 1,
+        }
+    }
+
+    /// Packets and bytes on the wire for a run of `click_limit` clicks, or
+    /// `None` without a limit. Cycled addresses and the sequence argument
+    /// can make the real byte count differ slightly.
+    pub fn run_estimate(&self) -> Option<(u64, u64)> {
+        if self.click_limit == 0 {
+            return None;
+        }
+        let clicks = u64::from(self.click_limit);
+        let bytes_per_dest: u64 = self
+            .click_packets()
+            .iter()
+            .map(|packet| encoder::encode(packet).map_or(0, |bytes| bytes.len() as u64))
+            .sum();
+        Some((
+            clicks * self.packets_per_click(),
+            clicks * bytes_per_dest * self.dests_per_click(),
+        ))
+    }
+
+    /// Shortest time from one click to the next that stays within
+    /// `max_packets_per_sec`.
+    pub fn min_click_period_ms(&self) -> u64 {
+        if self.max_packets_per_sec == 0 {
+            return 0;
+        }
+        (1000 * self.packets_per_click()).div_ceil(u64::from(self.max_packets_per_sec))
+    }
+
+    /// A momentary click can be held as long as the interval or longer, so
+    /// the release is followed at once by the next press and the input
+    /// never looks released. Ramps and randomization count by their
+    /// extremes.
+    pub fn hold_exceeds_interval(&self) -> bool {
+        if self.sends_single_message() || self.input_mode == InputMode::Toggle {
+            return false;
+        }
+        let hold_ms = if self.randomize_hold {
+            self.hold_min_ms.max(self.hold_max_ms)
+        } else {
+            self.hold_ms
+        };
+        let interval_ms = if self.ramp_enabled {
+            self.ramp_start_ms.min(self.ramp_end_ms)
+        } else if self.randomize_interval {
+            self.interval_bounds().0
+        } else {
+            self.interval_ms
+        };
+        hold_ms >= interval_ms
+    }
+
+    /// Whether the rate cap slows clicks down below the configured interval.
+    pub fn rate_limited(&self) -> bool {
+        let hold_ms = if self.randomize_hold {
+            self.hold_min_ms.min(self.hold_max_ms)
+        } else {
+            self.hold_ms
+        };
+        let period = if self.sends_single_message() {
+            self.interval_ms
+        } else if self.randomize_interval {
+            self.interval_bounds().0.max(hold_ms + 1)
+        } else {
+            self.interval_ms.max(hold_ms + 1)
+        };
+        period < self.min_click_period_ms()
+    }
+
+    /// Picks how long to hold the next click, sampling a fresh value when
+    /// hold randomization is enabled.
+    pub fn next_hold_ms(&self) -> u64 {
+        if !self.randomize_hold {
+            return self.hold_ms;
+        }
+        let low = self.hold_min_ms.min(self.hold_max_ms);
+        let high = self.hold_min_ms.max(self.hold_max_ms);
+        rand::random_range(low..=high)
+    }
+
+    /// Picks the interval for the next click, sampling a fresh value when
+    /// randomization is enabled.
+    pub fn next_interval_ms(&self) -> u64 {
+        if !self.randomize_interval {
+            return self.interval_ms;
+        }
+        let (low, high) = self.interval_bounds();
+        match self.jitter {
+            Jitter::Uniform => rand::random_range(low..=high),
+            Jitter::Gaussian => {
+                // A standard deviation of zero is valid and always yields the
+                // mean; only non-finite values fail.
+                let normal = Normal::new(self.interval_ms as f64, self.jitter_std_dev_ms as f64)
+                    .expect("finite standard deviation");
+                let sample = normal.sample(&mut rand::rng()).round();
+                sample.clamp(low as f64, high as f64) as u64
+            }
+        }
+    }
+
+    /// The ramped interval `elapsed` into a session.
+    pub fn ramp_interval_ms(&self, elapsed: Duration) -> u64 {
+        let progress = if self.ramp_secs == 0 {
+            1.0
+        } else {
+            (elapsed.as_secs_f64() / self.ramp_secs as f64).min(1.0)
+        };
+        let (start, end) = (self.ramp_start_ms as f64, self.ramp_end_ms as f64);
+        (start + (end - start) * progress).round() as u64
+    }
+
+    /// Shortest and longest interval randomization can pick.
+    pub fn interval_bounds(&self) -> (u64, u64) {
+        match self.jitter {
+            Jitter::Uniform => (
+                self.interval_min_ms.min(self.interval_max_ms),
+                self.interval_min_ms.max(self.interval_max_ms),
+            ),
+            Jitter::Gaussian => {
+                // The high bound never drops below the low one, even for an
+                // interval under the shortest allowed.
+                let spread = self.jitter_std_dev_ms.saturating_mul(3);
+                let low = self
+                    .interval_ms
+                    .saturating_sub(spread)
+                    .max(*INTERVAL_RANGE.start());
+                (low, self.interval_ms.saturating_add(spread).max(low))
+            }
+        }
+    }
+}
+
+/// Everything the send socket is created from, so a change to any of it
+/// means rebinding.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct SocketConfig {
+    pub bind_ip: IpAddr,
+    pub send_buffer_bytes: u32,
+    pub reuse_address: bool,
+    /// Multicast TTL and loopback, when sending to a multicast group.
+    pub multicast: Option<(u32, bool)>,
+    /// `SO_BROADCAST`, which IPv4 needs to send to a broadcast address.
+    pub broadcast: bool,
+}
+
+impl SocketConfig {
+    fn open(&self) -> io::Result<UdpSocket> {
+        let local = SocketAddr::new(self.bind_ip, 0);
+        let socket = Socket::new(Domain::for_address(local), Type::DGRAM, Some(Protocol::UDP))?;
+        if self.reuse_address {
+            socket.set_reuse_address(true)?;
+        }
+        if self.send_buffer_bytes > 0 {
+            socket.set_send_buffer_size(self.send_buffer_bytes as usize)?;
+        }
+        if let Some((ttl, loopback)) = self.multicast {
+            socket.set_multicast_ttl_v4(ttl)?;
+            socket.set_multicast_loop_v4(loopback)?;
+        }
+        if self.broadcast {
+            socket.set_broadcast(true)?;
+        }
+        socket.bind(&local.into())?;
+        socket.set_write_timeout(Some(SEND_TIMEOUT))?;
+        Ok(socket.into())
+    }
+}
+
+/// Packets sent per whole second over the last [`RATE_WINDOW_SECS`].
+pub struct RateHistory {
+    origin: Instant,
+    /// Seconds since `origin` that had sends, with their counts, oldest
+    /// first.
+    buckets: VecDeque<(u64, u32)>,
+}
+
+impl RateHistory {
+    pub fn new() -> Self {
+        Self {
+            origin: Instant::now(),
+            buckets: VecDeque::new(),
+        }
+    }
+
+    pub fn record(&mut self, at: Instant) {
+        let second = at.saturating_duration_since(self.origin).as_secs();
+        match self.buckets.back_mut() {
+            Some((last, count)) if *last == second => *count += 1,
+            _ => self.buckets.push_back((second, 1)),
+        }
+        while self
+            .buckets
+            .front()
+            .is_some_and(|&(first, _)| first + RATE_WINDOW_SECS <= second)
+        {
+            self.buckets.pop_front();
+        }
+    }
+
+    /// Packets per second for each completed second of the window as of
+    /// `now`, as (seconds before now, count), oldest first. Seconds without
+    /// sends are included as 0.
+    pub fn points(&self, now: Instant) -> Vec<[f64; 2]> {
+        let current = now.saturating_duration_since(self.origin).as_secs();
+        let first = current.saturating_sub(RATE_WINDOW_SECS);
+        (first..current)
+            .map(|second| {
+                let count = self
+                    .buckets
+                    .iter()
+                    .find(|&&(at, _)| at == second)
+                    .map_or(0, |&(_, count)| count);
+                [second as f64 - current as f64, f64::from(count)]
+            })
+            .collect()
+    }
+}
+
+impl Default for RateHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct AppState {
+    pub settings: Settings,
+    pub is_sending: bool,
+    pub clicks_sent: u32,
+    /// Clicks of every session since this state was created, for lifetime
+    /// statistics.
+    pub lifetime_clicks: u64,
+    /// Unpaused time of the sessions that have already ended.
+    ended_sessions: Duration,
+    pub started_at: Option<Instant>,
+    /// Sending is on but temporarily halted by the user. Counters and the
+    /// runtime timer are kept, so resuming continues the same session.
+    pub paused: bool,
+    /// The window lost focus while `pause_when_unfocused` is on. Kept apart
+    /// from `paused` so refocusing never undoes a manual pause.
+    pub unfocused: bool,
+    /// VRChat isn't running while `only_when_vrchat` is on.
+    pub vrchat_missing: bool,
+    /// `dest_host` is set but hasn't resolved, which pauses sending.
+    pub host_unresolved: bool,
+    pub host_error: Option<String>,
+    /// Whether a VRChat process was found, once the first scan is done.
+    pub vrchat_running: Option<bool>,
+    /// When the current pause began, so the runtime timer can skip it.
+    paused_at: Option<Instant>,
+    /// When the start delay ends, while the sender thread is waiting for it.
+    pub starting_at: Option<Instant>,
+    /// When the last click started and when the next one is due, as of the
+    /// latest rest. Cleared when sending stops.
+    pub next_click: Option<(Instant, Instant)>,
+    pub packets_sent: u64,
+    pub send_rate: RateHistory,
+    /// Next sequence number for `append_sequence`. Counts packets, not
+    /// destinations, and restarts from 0 with each sending session.
+    pub sequence: i32,
+    /// Position in [`Settings::cycle_addresses`] of the next click. Restarts
+    /// from the first address with each sending session.
+    pub cycle_index: usize,
+    /// Destination of the next click in round-robin mode. Restarts from the
+    /// first port with each sending session.
+    pub dest_index: usize,
+    /// The state toggle mode sent last. Kept across sessions, so the next
+    /// flip continues from it.
+    pub toggle_on: bool,
+    /// While loaded, sending plays this instead of clicking.
+    pub script: Option<Arc<Script>>,
+    pub recording: Option<Recording>,
+    pub last_sent: Option<Instant>,
+    pub last_error: Option<String>,
+    /// Sends that failed in a row, reset by the next success.
+    pub send_failures: u32,
+    /// Retries of failed sends, and sends still failing after all of them.
+    pub retries: u64,
+    pub failed_sends: u64,
+    /// When [`Shared::emergency_stop`] last ran.
+    pub stopped_at: Option<Instant>,
+    /// An emergency stop silenced the heartbeat and ping too, until sending
+    /// is next turned on.
+    pub halted: bool,
+    /// A momentary press went out without its release yet.
+    pub held: bool,
+    /// Machines other than this one that sending may start to without
+    /// asking. Kept in sync with the config by the UI.
+    pub trusted_ips: Vec<IpAddr>,
+    /// Hostnames trusted the same way, whatever they resolve to.
+    pub trusted_hosts: Vec<String>,
+    /// A start held back by [`Self::request_start`] until the UI confirms
+    /// this destination: the hostname if one is set, else the IP.
+    pub confirm_start: Option<String>,
+    /// Where the click socket is bound, or why binding it failed.
+    pub local_addr: Option<Result<SocketAddr, String>>,
+    pub listen_error: Option<String>,
+    pub http_error: Option<String>,
+    pub ping: Ping,
+    /// Most recent sent and received messages, oldest first, capped at
+    /// [`LOG_CAPACITY`].
+    pub log: VecDeque<LogEntry>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Sent,
+    Received,
+}
+
+#[derive(Clone)]
+pub struct LogEntry {
+    pub at: Instant,
+    pub time: DateTime<Local>,
+    pub direction: Direction,
+    /// A dry-run send that never left the app.
+    pub dry_run: bool,
+    pub address: String,
+    /// Only the first argument is kept.
+    pub value: OscType,
+    /// Where a sent message went, or where a received one came from.
+    pub dests: Vec<SocketAddr>,
+}
+
+impl AppState {
+    pub fn set_sending(&mut self, sending: bool) {
+        if sending {
+            self.halted = false;
+        }
+        if sending && !self.is_sending {
+            self.clicks_sent = 0;
+            self.sequence = 0;
+            self.cycle_index = 0;
+            self.dest_index = 0;
+            self.started_at = Some(Instant::now());
+        } else if !sending {
+            self.ended_sessions += self.session_elapsed().unwrap_or_default();
+            self.started_at = None;
+            self.next_click = None;
+            self.paused = false;
+            self.paused_at = None;
+        }
+        self.is_sending = sending;
+        self.sync_paused_at();
+    }
+
+    /// Turns sending on, unless the destination is another machine that
+    /// isn't trusted: then sending stays off and the UI is asked to confirm.
+    /// Every trigger (UI, hotkeys, tray, HTTP, schedule) starts through this.
+    /// Returns whether sending is on.
+    pub fn request_start(&mut self) -> bool {
+        match self.untrusted_destination() {
+            Some(dest) if !self.is_sending => self.confirm_start = Some(dest),
+            Some(_) => {}
+            None => self.set_sending(true),
+        }
+        self.is_sending
+    }
+
+    /// The destination a start needs confirming for, if any. A hostname is
+    /// judged by name, since `dest_ip` is stale until it resolves.
+    pub fn untrusted_destination(&self) -> Option<String> {
+        let host = self.settings.dest_host.trim();
+        if !host.is_empty() {
+            let trusted = host.eq_ignore_ascii_case("localhost")
+                || self
+                    .trusted_hosts
+                    .iter()
+                    .any(|trusted| trusted.eq_ignore_ascii_case(host));
+            return (!trusted).then(|| host.to_string());
+        }
+        let ip = self.settings.dest_ip;
+        (!ip.is_loopback() && !self.trusted_ips.contains(&ip)).then(|| ip.to_string())
+    }
+
+    /// Whether a one-off send (a test click, an HTTP `/click`, a MIDI note)
+    /// may go out: to a trusted destination, or to one sending was already
+    /// confirmed for. Otherwise the refusal is reported in `last_error`.
+    pub fn allows_one_off(&mut self) -> bool {
+        if self.is_sending {
+            return true;
+        }
+        let Some(dest) = self.untrusted_destination() else {
+            return true;
+        };
+        self.last_error = Some(format!(
+            "Not sent: {dest} isn't trusted yet, start sending to it first"
+        ));
+        false
+    }
+
+    /// Stops sending, or starts it through [`Self::request_start`].
+    pub fn toggle_sending(&mut self) {
+        if self.is_sending {
+            self.set_sending(false);
+        } else {
+            self.request_start();
+        }
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        if !self.is_sending || paused == self.paused {
+            return;
+        }
+        self.paused = paused;
+        self.sync_paused_at();
+    }
+
+    pub fn set_unfocused(&mut self, unfocused: bool) {
+        self.unfocused = unfocused;
+        self.sync_paused_at();
+    }
+
+    pub fn set_vrchat_missing(&mut self, missing: bool) {
+        self.vrchat_missing = missing;
+        self.sync_paused_at();
+    }
+
+    pub fn set_host_unresolved(&mut self, unresolved: bool) {
+        self.host_unresolved = unresolved;
+        self.sync_paused_at();
+    }
+
+    /// Sending is on but halted, manually, because the window lost focus,
+    /// because VRChat isn't running or because the destination hostname
+    /// doesn't resolve.
+    pub fn is_paused(&self) -> bool {
+        self.is_sending
+            && (self.paused || self.unfocused || self.vrchat_missing || self.host_unresolved)
+    }
+
+    /// Starts or ends the pause timer when [`Self::is_paused`] changes, so the
+    /// runtime timer skips every pause whatever caused it.
+    fn sync_paused_at(&mut self) {
+        match (self.is_paused(), self.paused_at) {
+            (true, None) => self.paused_at = Some(Instant::now()),
+            (false, Some(paused_at)) => {
+                self.paused_at = None;
+                if let Some(started_at) = &mut self.started_at {
+                    *started_at += paused_at.elapsed();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Unpaused time since the first click of the session.
+    pub fn session_elapsed(&self) -> Option<Duration> {
+        let started_at = self.started_at?;
+        Some(
+            self.paused_at
+                .unwrap_or_else(Instant::now)
+                .saturating_duration_since(started_at),
+        )
+    }
+
+    /// Unpaused sending time of every session, the current one included.
+    pub fn lifetime_runtime(&self) -> Duration {
+        self.ended_sessions + self.session_elapsed().unwrap_or_default()
+    }
+
+    /// Time left before the max runtime stops sending, if one is set.
+    pub fn runtime_remaining(&self) -> Option<Duration> {
+        let elapsed = self.session_elapsed()?;
+        let max_runtime = Duration::from_secs(self.settings.max_runtime_secs);
+        (!max_runtime.is_zero()).then(|| max_runtime.saturating_sub(elapsed))
+    }
+
+    /// The settings with `interval_ms` taken from the ramp, when it is on.
+    /// Before the first click the ramp is at its start.
+    pub fn effective_settings(&self) -> Cow<'_, Settings> {
+        if !self.settings.ramp_enabled {
+            return Cow::Borrowed(&self.settings);
+        }
+        let elapsed = self.session_elapsed().unwrap_or_default();
+        Cow::Owned(Settings {
+            interval_ms: self.settings.ramp_interval_ms(elapsed),
+            ..self.settings.clone()
+        })
+    }
+
+    pub fn push_log(&mut self, entry: LogEntry) {
+        if self.log.len() == LOG_CAPACITY {
+            self.log.pop_front();
+        }
+        self.log.push_back(entry);
+    }
+
+    /// The cycle address for the next click, advancing the cycle or
+    /// drawing one by weight.
+    pub fn next_cycle_address(&mut self) -> Option<String> {
+        let pool = self.settings.cycle_pool();
+        if pool.is_empty() {
+            return None;
+        }
+        let weighted = self
+            .settings
+            .cycle_random
+            .then(|| WeightedIndex::new(pool.iter().map(|&(_, weight)| weight)).ok())
+            .flatten();
+        let index = match weighted {
+            Some(weights) => weights.sample(&mut rand::rng()),
+            None => self.cycle_index % pool.len(),
+        };
+        let address = pool[index].0.to_string();
+        self.cycle_index = index + 1;
+        Some(address)
+    }
+
+    /// The cycle address most recently pressed, which a release goes to.
+    pub fn current_cycle_address(&self) -> Option<String> {
+        let addresses = self.settings.cycle_addresses();
+        let last = (self.cycle_index + addresses.len()).checked_sub(1)?;
+        addresses
+            .get(last % addresses.len())
+            .map(|address| address.to_string())
+    }
+
+    /// In round-robin mode, which of `count` destinations the next click
+    /// goes to, advancing the turn.
+    pub fn next_dest_index(&mut self, count: usize) -> Option<usize> {
+        if self.settings.destination_mode != DestinationMode::RoundRobin || count == 0 {
+            return None;
+        }
+        let index = self.dest_index % count;
+        self.dest_index = index + 1;
+        Some(index)
+    }
+
+    /// In round-robin mode, the destination most recently pressed, which a
+    /// release goes to.
+    pub fn current_dest_index(&self, count: usize) -> Option<usize> {
+        if self.settings.destination_mode != DestinationMode::RoundRobin || count == 0 {
+            return None;
+        }
+        Some((self.dest_index + count - 1) % count)
+    }
+
+    pub fn limit_reached(&self) -> bool {
+        self.settings.click_limit > 0 && self.clicks_sent >= self.settings.click_limit
+    }
+}
+
+/// State shared with the sender thread. `wake` is notified on every change so
+/// the thread can block while idle instead of polling.
+pub struct Shared {
+    pub state: Mutex<AppState>,
+    pub(crate) wake: Condvar,
+    shutdown: AtomicBool,
+    /// One-off test clicks requested from the UI, independent of `is_sending`.
+    pending_clicks: AtomicU32,
+}
+
+impl Shared {
+    pub fn new(settings: Settings) -> Self {
+        Self {
+            state: Mutex::new(AppState {
+                settings,
+                is_sending: false,
+                clicks_sent: 0,
+                lifetime_clicks: 0,
+                ended_sessions: Duration::ZERO,
+                started_at: None,
+                paused: false,
+                unfocused: false,
+                vrchat_missing: false,
+                host_unresolved: false,
+                host_error: None,
+                vrchat_running: None,
+                paused_at: None,
+                starting_at: None,
+                next_click: None,
+                packets_sent: 0,
+                send_rate: RateHistory::new(),
+                sequence: 0,
+                cycle_index: 0,
+                dest_index: 0,
+                toggle_on: false,
+                script: None,
+                recording: None,
+                last_sent: None,
+                last_error: None,
+                send_failures: 0,
+                retries: 0,
+                failed_sends: 0,
+                stopped_at: None,
+                halted: false,
+                held: false,
+                trusted_ips: Vec::new(),
+                trusted_hosts: Vec::new(),
+                confirm_start: None,
+                local_addr: None,
+                listen_error: None,
+                http_error: None,
+                ping: Ping::default(),
+                log: VecDeque::with_capacity(LOG_CAPACITY),
+            }),
+            wake: Condvar::new(),
+            shutdown: AtomicBool::new(false),
+            pending_clicks: AtomicU32::new(0),
+        }
+    }
+
+    pub fn update(&self, f: impl FnOnce(&mut AppState)) {
+        f(&mut self.state.lock().unwrap());
+        self.wake.notify_all();
+    }
+
+    pub fn is_shutdown(&self) -> bool {
+        self.shutdown.load(Ordering::SeqCst)
+    }
+
+    /// Queues one click for the sender thread, unless the destination isn't
+    /// trusted (see [`AppState::allows_one_off`]).
+    pub fn request_click(&self) {
+        let mut state = self.state.lock().unwrap();
+        if !state.allows_one_off() {
+            return;
+        }
+        self.pending_clicks.fetch_add(1, Ordering::SeqCst);
+        self.wake.notify_all();
+    }
+
+    /// Stops sending and releases on every destination right away from the
+    /// calling thread. The sender thread cuts its current hold short and
+    /// leaves the release to this, and the heartbeat and ping go quiet until
+    /// sending is turned on again. Pending one-off clicks are dropped.
+    pub fn emergency_stop(&self) {
+        let settings = {
+            let mut state = self.state.lock().unwrap();
+            self.pending_clicks.store(0, Ordering::SeqCst);
+            state.set_sending(false);
+            state.stopped_at = Some(Instant::now());
+            state.halted = true;
+            state.settings.clone()
+        };
+        self.wake.notify_all();
+
+        // The same options as the click socket, or a broadcast or multicast
+        // destination would refuse the release.
+        let released = settings
+            .socket_config()
+            .open()
+            .and_then(|socket| release_to(self, &socket, &settings.destinations(), &settings));
+        if let Err(e) = released {
+            self.update(|s| s.last_error = Some(format!("Emergency release failed: {e}")));
+        }
+    }
+
+    /// Sends a press, or its release, right away from the calling thread,
+    /// for external triggers that decide how long the input is held, such
+    /// as a MIDI note. Presses are dropped while paused or to an untrusted
+    /// destination; the release of one still held goes through.
+    pub fn send_now(&self, pressed: bool) {
+        let settings = {
+            let mut state = self.state.lock().unwrap();
+            if (pressed || !state.held) && (state.is_paused() || !state.allows_one_off()) {
+                return;
+            }
+            state.settings.clone()
+        };
+        let socket = match settings.socket_config().open() {
+            Ok(socket) => socket,
+            Err(e) => {
+                let error = format!(
+                    "Failed to bind UDP socket to {}: {e}",
+                    settings.local_bind_ip()
+                );
+                self.update(|s| s.last_error = Some(error));
+                return;
+            }
+        };
+        // Send failures are reported by `send_packet`.
+        let dests = settings.destinations();
+        let _ = if pressed {
+            send_press(self, &socket, &dests, &settings)
+        } else {
+            send_release(self, &socket, &dests, &settings)
+        };
+    }
+
+    pub fn request_shutdown(&self) {
+        // Taking the lock orders the store against the thread's predicate
+        // check, so the notification cannot be missed.
+        let _state = self.state.lock().unwrap();
+        self.shutdown.store(true, Ordering::SeqCst);
+        self.wake.notify_all();
+    }
+}
+
+pub fn run_sender(shared: &Shared) {
+    sender_loop(shared, |shared| bind_socket(shared, true));
+}
+
+/// The body of [`run_sender`], with socket binding passed in so tests can
+/// send into a mock sink.
+fn sender_loop<S: Sink>(shared: &Shared, bind: impl Fn(&Shared) -> Option<(S, SocketConfig)>) {
+    let Some((mut socket, mut bound)) = bind(shared) else {
+        return;
+    };
+
+    // Set by the first click after sending is turned on. Whatever ends the
+    // session (unchecking, a limit, pausing then stopping, shutdown), the
+    // loop then comes around with `sending` false and releases exactly once,
+    // even if the click's own release already went out: UDP may have lost it.
+    // A stop during a hold skips the click's release and leaves it to this.
+    let mut session_active = false;
+    // `stopped_at` when the session began; an emergency stop since has sent
+    // the release already.
+    let mut session_stopped_at = None;
+    let mut burst_sent = 0;
+    // Rebinding is retried every `MAX_SEND_FAILURES` failures, not after each.
+    let mut rebind_at = MAX_SEND_FAILURES;
+
+    loop {
+        let (settings, sending, paused, single_clicks, send_failures) = {
+            let mut state = shared.state.lock().unwrap();
+            if !session_active {
+                state = shared
+                    .wake
+                    .wait_while(state, |s| {
+                        !s.is_sending
+                            && shared.pending_clicks.load(Ordering::SeqCst) == 0
+                            && !shared.is_shutdown()
+                    })
+                    .unwrap();
+            }
+            if state.is_paused() {
+                state = shared
+                    .wake
+                    .wait_while(state, |s| {
+                        s.is_paused()
+                            && shared.pending_clicks.load(Ordering::SeqCst) == 0
+                            && !shared.is_shutdown()
+                    })
+                    .unwrap();
+            }
+            if state.is_sending && state.runtime_remaining() == Some(Duration::ZERO) {
+                state.set_sending(false);
+                notify(&state.settings, "Stopped: max runtime reached");
+            }
+            (
+                state.effective_settings().into_owned(),
+                state.is_sending,
+                state.is_paused(),
+                shared.pending_clicks.swap(0, Ordering::SeqCst),
+                state.send_failures,
+            )
+        };
+        let dests = settings.destinations();
+
+        if send_failures < MAX_SEND_FAILURES {
+            rebind_at = MAX_SEND_FAILURES;
+        }
+        let failing = send_failures >= rebind_at;
+        if failing {
+            rebind_at = send_failures + MAX_SEND_FAILURES;
+        }
+        if (failing || settings.socket_config() != bound)
+            && let Some(rebound) = bind(shared)
+        {
+            (socket, bound) = rebound;
+        }
+
+        let shutting_down = shared.is_shutdown();
+
+        if !shutting_down {
+            for _ in 0..single_clicks {
+                let _ = send_input(shared, &socket, &dests, &settings);
+            }
+            // A stop cut a click's hold short, and no session end follows to
+            // release it.
+            if single_clicks > 0 && !session_active && shared.state.lock().unwrap().held {
+                let _ = send_release(shared, &socket, &dests, &settings);
+            }
+        }
+
+        // Woken by a single click while paused; keep the session as it is.
+        if paused && !shutting_down {
+            continue;
+        }
+
+        if sending && !shutting_down {
+            if !session_active && settings.start_delay_ms > 0 {
+                let delay = Duration::from_millis(settings.start_delay_ms);
+                let mut state = shared.state.lock().unwrap();
+                state.starting_at = Some(Instant::now() + delay);
+                state = shared
+                    .wake
+                    .wait_timeout_while(state, delay, |s| {
+                        s.is_sending && !s.is_paused() && !shared.is_shutdown()
+                    })
+                    .unwrap()
+                    .0;
+                state.starting_at = None;
+                if !state.is_sending || state.is_paused() || shared.is_shutdown() {
+                    continue;
+                }
+                // The max runtime counts from the first click, not the toggle.
+                state.started_at = Some(Instant::now());
+            }
+            if !session_active {
+                notify(&settings, "Sending started");
+                session_stopped_at = shared.state.lock().unwrap().stopped_at;
+            }
+
+            let script = shared.state.lock().unwrap().script.clone();
+            if let Some(script) = script {
+                play_script(shared, &socket, &dests, &script, settings.script_loop);
+                continue;
+            }
+
+            let click_from = Instant::now();
+            if settings.sweep_mode {
+                let elapsed = shared
+                    .state
+                    .lock()
+                    .unwrap()
+                    .started_at
+                    .map_or(Duration::ZERO, |started_at| started_at.elapsed());
+                let _ = send_packet(shared, &socket, &dests, &settings.sweep_packet(elapsed));
+            } else if settings.text_mode {
+                let _ = send_packet(shared, &socket, &dests, &settings.text_packet());
+            } else {
+                let _ = send_input(shared, &socket, &dests, &settings);
+            }
+            session_active = true;
+
+            {
+                let mut state = shared.state.lock().unwrap();
+                state.clicks_sent += 1;
+                state.lifetime_clicks += 1;
+                if state.limit_reached() {
+                    state.set_sending(false);
+                    notify(&settings, "Stopped: click limit reached");
+                    continue;
+                }
+            }
+
+            burst_sent += 1;
+            let burst_done = !settings.sends_single_message()
+                && settings.burst_mode
+                && burst_sent >= settings.burst_count;
+            if burst_done {
+                burst_sent = 0;
+            }
+            let sampled_ms = settings.next_interval_ms();
+            let held_ms = if settings.sends_single_message() {
+                0
+            } else {
+                click_from.elapsed().as_millis() as u64
+            };
+
+            // Wait out the rest in steps, recomputing it from the current
+            // settings on every wake so edits apply to the rest in progress.
+            let rest_from = Instant::now();
+            let mut state = shared.state.lock().unwrap();
+            loop {
+                let mut left =
+                    rest_after_click(&state.effective_settings(), sampled_ms, held_ms, burst_done)
+                        .saturating_sub(rest_from.elapsed());
+                if let Some(remaining) = state.runtime_remaining() {
+                    left = left.min(remaining);
+                }
+                if left.is_zero() || !state.is_sending || state.is_paused() || shared.is_shutdown()
+                {
+                    break;
+                }
+                state.next_click = Some((click_from, Instant::now() + left));
+                state = shared.wake.wait_timeout(state, left).unwrap().0;
+            }
+            continue;
+        }
+
+        if session_active {
+            // A toggle keeps its state when sending stops, and a text has
+            // nothing to release.
+            let stopped = shared.state.lock().unwrap().stopped_at != session_stopped_at;
+            if stopped {
+                // The emergency stop has released already.
+            } else if settings.sweep_mode {
+                let _ = send_packet(shared, &socket, &dests, &settings.sweep_end_packet());
+            } else if !settings.text_mode && settings.input_mode == InputMode::Momentary {
+                let _ = send_release(shared, &socket, &dests, &settings);
+            }
+            session_active = false;
+            burst_sent = 0;
+        }
+
+        if shutting_down {
+            return;
+        }
+    }
+}
+
+/// Runs until shutdown, turning sending on when the clock enters the enabled
+/// schedule window and off when it leaves. Only the crossings act, so a
+/// manual toggle inside or outside the window is left alone.
+pub fn run_scheduler(shared: &Shared) {
+    let mut was_in_window = false;
+    let mut state = shared.state.lock().unwrap();
+    loop {
+        if shared.is_shutdown() {
+            return;
+        }
+        let schedule = state.settings.schedule;
+        if schedule.enabled {
+            let in_window = schedule.contains(minute_of_day(Local::now()));
+            if in_window != was_in_window {
+                if !in_window && state.is_sending {
+                    notify(&state.settings, "Stopped: schedule window ended");
+                }
+                if in_window {
+                    state.request_start();
+                } else {
+                    state.set_sending(false);
+                }
+                shared.wake.notify_all();
+                was_in_window = in_window;
+            }
+        } else {
+            was_in_window = false;
+        }
+        state = shared
+            .wake
+            .wait_timeout_while(state, SCHEDULE_POLL_INTERVAL, |s| {
+                s.settings.schedule == schedule && !shared.is_shutdown()
+            })
+            .unwrap()
+            .0;
+    }
+}
+
+/// Sleeps for `delay` of unpaused time within the current sending session.
+/// Returns `false` as soon as sending stops or shutdown is requested.
+pub(crate) fn sleep_in_session(shared: &Shared, delay: Duration) -> bool {
+    let mut left = delay;
+    let mut state = shared.state.lock().unwrap();
+    loop {
+        if !state.is_sending || shared.is_shutdown() {
+            return false;
+        }
+        if state.is_paused() {
+            state = shared
+                .wake
+                .wait_while(state, |s| s.is_paused() && !shared.is_shutdown())
+                .unwrap();
+            continue;
+        }
+        if left.is_zero() {
+            return true;
+        }
+        let from = Instant::now();
+        state = shared
+            .wake
+            .wait_timeout_while(state, left, |s| {
+                s.is_sending && !s.is_paused() && !shared.is_shutdown()
+            })
+            .unwrap()
+            .0;
+        left = left.saturating_sub(from.elapsed());
+    }
+}
+
+/// How long to wait after a click before the next one. `sampled_ms` is the
+/// interval drawn for this click, kept within the current random range, and
+/// `held_ms` how long the click itself took.
+fn rest_after_click(
+    settings: &Settings,
+    sampled_ms: u64,
+    held_ms: u64,
+    burst_done: bool,
+) -> Duration {
+    let rest_ms = if settings.sends_single_message() {
+        settings.interval_ms
+    } else if burst_done {
+        settings.burst_pause_ms
+    } else if settings.randomize_interval {
+        let (low, high) = settings.interval_bounds();
+        sampled_ms.clamp(low, high).saturating_sub(held_ms)
+    } else {
+        settings.interval_ms.saturating_sub(held_ms)
+    }
+    .max(1);
+    Duration::from_millis(rest_ms.max(settings.min_click_period_ms().saturating_sub(held_ms)))
+}
+
+/// Runs until shutdown, sending the heartbeat whenever it is enabled. Kept
+/// apart from [`run_sender`] so clicks, pauses and start delays never hold
+/// it up.
+pub fn run_heartbeat(shared: &Shared) {
+    let Some((mut socket, mut bound)) = bind_socket(shared, false) else {
+        return;
+    };
+
+    loop {
+        let settings = {
+            let state = shared
+                .wake
+                .wait_while(shared.state.lock().unwrap(), |s| {
+                    (!s.settings.heartbeat_enabled || s.halted) && !shared.is_shutdown()
+                })
+                .unwrap();
+            if shared.is_shutdown() {
+                return;
+            }
+            state.settings.clone()
+        };
+
+        if settings.socket_config() != bound {
+            match bind_socket(shared, false) {
+                Some(rebound) => (socket, bound) = rebound,
+                None => return,
+            }
+        }
+
+        if is_valid_address(&settings.heartbeat_address) {
+            let packet = OscPacket::Message(OscMessage {
+                addr: settings.heartbeat_address.clone(),
+                args: vec![OscType::Bool(true)],
+            });
+            let _ = send_quiet(shared, &socket, &settings.destinations(), &packet);
+        }
+
+        // Changing the interval restarts the wait with the new one.
+        let interval = settings.heartbeat_interval_ms;
+        let _ = shared
+            .wake
+            .wait_timeout_while(
+                shared.state.lock().unwrap(),
+                Duration::from_millis(interval.max(1)),
+                |s| {
+                    s.settings.heartbeat_enabled
+                        && !s.halted
+                        && s.settings.heartbeat_interval_ms == interval
+                        && !shared.is_shutdown()
+                },
+            )
+            .unwrap();
+    }
+}
+
+/// Binds the sending socket per [`Settings::socket_config`], retrying every
+/// [`BIND_RETRY_INTERVAL`] until it succeeds. The settings are re-read on
+/// each attempt, so picking another address ends a failing retry loop.
+/// Returns the socket with the config it was created from, or `None` if
+/// shutdown is requested while waiting. The click socket reports the outcome
+/// in [`AppState::local_addr`].
+pub(crate) fn bind_socket(
+    shared: &Shared,
+    report_local_addr: bool,
+) -> Option<(UdpSocket, SocketConfig)> {
+    loop {
+        let config = shared.state.lock().unwrap().settings.socket_config();
+        match config.open() {
+            Ok(socket) => {
+                let local_addr = socket.local_addr().map_err(|e| e.to_string());
+                shared.update(|s| {
+                    s.last_error = None;
+                    if report_local_addr {
+                        s.local_addr = Some(local_addr);
+                    }
+                });
+                return Some((socket, config));
+            }
+            Err(e) => {
+                let mut state = shared.state.lock().unwrap();
+                let error = format!("Failed to bind UDP socket to {}: {e}", config.bind_ip);
+                if report_local_addr {
+                    state.local_addr = Some(Err(error.clone()));
+                }
+                state.last_error = Some(error);
+                let _ = shared
+                    .wake
+                    .wait_timeout_while(state, BIND_RETRY_INTERVAL, |s| {
+                        s.settings.socket_config() == config && !shared.is_shutdown()
+                    })
+                    .unwrap();
+            }
+        }
+
+        if shared.is_shutdown() {
+            return None;
+        }
+    }
+}
+
+/// Addresses of the local network interfaces, for choosing `bind_ip`.
+pub fn local_interfaces() -> Vec<(String, IpAddr)> {
+    if_addrs::get_if_addrs()
+        .map(|interfaces| {
+            interfaces
+                .into_iter()
+                .map(|interface| (interface.name.clone(), interface.ip()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The port `delta` away from `port`, clamped to the valid range, or `None`
+/// when that leaves it unchanged.
+pub fn nudge_port(port: u16, delta: i32) -> Option<u16> {
+    let next = (i32::from(port) + delta).clamp(0, i32::from(u16::MAX)) as u16;
+    (next != port).then_some(next)
+}
+
+pub fn is_valid_address(address: &str) -> bool {
+    address.starts_with('/') && !address.contains(char::is_whitespace)
+}
+
+/// Where encoded packets go. Abstracted so the send path can be tested
+/// without real networking.
+pub trait Sink {
+    fn send(&self, bytes: &[u8], addr: SocketAddr) -> io::Result<usize>;
+}
+
+impl Sink for UdpSocket {
+    fn send(&self, bytes: &[u8], addr: SocketAddr) -> io::Result<usize> {
+        self.send_to(bytes, addr)
+    }
+}
+
+/// One click in the configured [`InputMode`].
+pub fn send_input(
+    shared: &Shared,
+    sink: &dyn Sink,
+    dests: &[SocketAddr],
+    settings: &Settings,
+) -> io::Result<()> {
+    match settings.input_mode {
+        InputMode::Momentary => send_click(shared, sink, dests, settings),
+        InputMode::Toggle => send_toggle(shared, sink, dests, settings),
+    }
+}
+
+/// Flips the toggle state and sends the new one.
+pub fn send_toggle(
+    shared: &Shared,
+    sink: &dyn Sink,
+    dests: &[SocketAddr],
+    settings: &Settings,
+) -> io::Result<()> {
+    let on = {
+        let mut state = shared.state.lock().unwrap();
+        state.toggle_on = !state.toggle_on;
+        state.toggle_on
+    };
+    send_packet(shared, sink, dests, &settings.click_packet(on))
+}
+
+pub fn send_click(
+    shared: &Shared,
+    sink: &dyn Sink,
+    dests: &[SocketAddr],
+    settings: &Settings,
+) -> io::Result<()> {
+    let (stopped_before, was_sending) = {
+        let state = shared.state.lock().unwrap();
+        (state.stopped_at, state.is_sending)
+    };
+    let pressed = send_press(shared, sink, dests, settings);
+    let hold = Duration::from_millis(settings.next_hold_ms().max(1));
+    // Stopping sending cuts the hold short, leaving the release to the end
+    // of the session, which follows at once. An emergency stop has already
+    // released.
+    let state = shared
+        .wake
+        .wait_timeout_while(shared.state.lock().unwrap(), hold, |s| {
+            s.stopped_at == stopped_before
+                && (s.is_sending || !was_sending)
+                && !shared.is_shutdown()
+        })
+        .unwrap()
+        .0;
+    if state.stopped_at != stopped_before || (was_sending && !state.is_sending) {
+        return pressed;
+    }
+    drop(state);
+    let released = send_release(shared, sink, dests, settings);
+    pressed.and(released)
+}
+
+/// Sends the press of a click, moving on to the next cycle address and
+/// round-robin port. [`send_release`] ends it.
+pub fn send_press(
+    shared: &Shared,
+    sink: &dyn Sink,
+    dests: &[SocketAddr],
+    settings: &Settings,
+) -> io::Result<()> {
+    let (cycled, dest) = {
+        let mut state = shared.state.lock().unwrap();
+        state.held = true;
+        (
+            state.next_cycle_address(),
+            state.next_dest_index(dests.len()),
+        )
+    };
+    send_packet(
+        shared,
+        sink,
+        pick_dest(dests, dest),
+        &with_address(settings, cycled).click_packet(true),
+    )
+}
+
+pub fn send_release(
+    shared: &Shared,
+    sink: &dyn Sink,
+    dests: &[SocketAddr],
+    settings: &Settings,
+) -> io::Result<()> {
+    let dest = shared.state.lock().unwrap().current_dest_index(dests.len());
+    release_to(shared, sink, pick_dest(dests, dest), settings)
+}
+
+/// Sends the release of the last pressed cycle address to all of `dests`.
+fn release_to(
+    shared: &Shared,
+    sink: &dyn Sink,
+    dests: &[SocketAddr],
+    settings: &Settings,
+) -> io::Result<()> {
+    let cycled = shared.state.lock().unwrap().current_cycle_address();
+    send_packet(
+        shared,
+        sink,
+        dests,
+        &with_address(settings, cycled).click_packet(false),
+    )?;
+    shared.update(|s| s.held = false);
+    Ok(())
+}
+
+/// The round-robin destination, or all of them when there's no turn.
+fn pick_dest(dests: &[SocketAddr], index: Option<usize>) -> &[SocketAddr] {
+    match index {
+        Some(index) => &dests[index..=index],
+        None => dests,
+    }
+}
+
+/// The settings with the main address replaced by a cycled one, if any.
+fn with_address(settings: &Settings, address: Option<String>) -> Cow<'_, Settings> {
+    match address {
+        Some(address) => Cow::Owned(Settings {
+            address,
+            ..settings.clone()
+        }),
+        None => Cow::Borrowed(settings),
+    }
+}
+
+/// Sends a single immediate message as is. Several messages, or any with a
+/// delay, are wrapped in a bundle so the receiver applies them together at
+/// the timetag.
+fn build_packet(mut messages: Vec<OscMessage>, delay: Duration) -> OscPacket {
+    if messages.len() == 1 && delay.is_zero() {
+        return OscPacket::Message(messages.remove(0));
+    }
+    // A clock before 1970 can't be encoded; fall back to sending immediately.
+    let timetag = (!delay.is_zero())
+        .then(|| OscTime::try_from(SystemTime::now() + delay).ok())
+        .flatten()
+        .unwrap_or(OSC_IMMEDIATELY);
+    OscPacket::Bundle(OscBundle {
+        timetag,
+        content: messages.into_iter().map(OscPacket::Message).collect(),
+    })
+}
+
+/// Encodes a packet and sends it to every destination, recording the outcome
+/// in the shared stats and error fields. All destinations are tried even if
+/// one fails; the first error is returned.
+pub fn send_packet(
+    shared: &Shared,
+    sink: &dyn Sink,
+    dests: &[SocketAddr],
+    packet: &OscPacket,
+) -> io::Result<()> {
+    let (dry_run, sequence, retries) = {
+        let mut state = shared.state.lock().unwrap();
+        let sequence = state.settings.append_sequence.then(|| {
+            let sequence = state.sequence;
+            state.sequence = sequence.wrapping_add(1);
+            sequence
+        });
+        let retries = state.settings.send_retries.min(MAX_SEND_RETRIES);
+        (state.settings.dry_run, sequence, retries)
+    };
+    let packet = match sequence {
+        Some(sequence) => Cow::Owned(with_sequence(packet.clone(), sequence)),
+        None => Cow::Borrowed(packet),
+    };
+
+    let buf = match encoder::encode(&packet) {
+        Ok(buf) => buf,
+        Err(e) => {
+            shared.update(|s| s.last_error = Some(format!("Failed to encode OSC: {e}")));
+            return Err(io::Error::other(e));
+        }
+    };
+
+    let mut result = Ok(());
+    for &dest in dests {
+        let send = || {
+            if dry_run {
+                Ok(())
+            } else {
+                sink.send(&buf, dest).map(|_| ())
+            }
+        };
+        let mut sent = send();
+        for attempt in 0..retries {
+            if sent.is_ok() {
+                break;
+            }
+            thread::sleep((RETRY_BACKOFF * 2u32.pow(attempt)).min(MAX_RETRY_BACKOFF));
+            shared.state.lock().unwrap().retries += 1;
+            sent = send();
+        }
+
+        let mut state = shared.state.lock().unwrap();
+        match &sent {
+            Ok(()) => {
+                let now = Instant::now();
+                state.packets_sent += 1;
+                state.send_rate.record(now);
+                state.last_sent = Some(now);
+                state.last_error = None;
+                state.send_failures = 0;
+            }
+            Err(e) => {
+                state.last_error = Some(format!("Failed to send OSC to {dest}: {e}"));
+                state.send_failures += 1;
+                state.failed_sends += 1;
+            }
+        }
+        drop(state);
+
+        result = result.and(sent);
+    }
+
+    log_sent(&mut shared.state.lock().unwrap(), &packet, dests, dry_run);
+    result
+}
+
+/// Sends a packet beside the clicks, like a heartbeat or ping: as is, with
+/// no sequence number, retries, stats, log entry or recording, so it
+/// neither shows up as clicks nor counts toward rebinding the click socket.
+/// Dry run still holds it back.
+pub fn send_quiet(
+    shared: &Shared,
+    sink: &dyn Sink,
+    dests: &[SocketAddr],
+    packet: &OscPacket,
+) -> io::Result<()> {
+    if shared.state.lock().unwrap().settings.dry_run {
+        return Ok(());
+    }
+    let buf = encoder::encode(packet).map_err(io::Error::other)?;
+    dests
+        .iter()
+        .map(|&dest| sink.send(&buf, dest).map(|_| ()))
+        .fold(Ok(()), io::Result::and)
+}
+
+fn with_sequence(packet: OscPacket, sequence: i32) -> OscPacket {
+    match packet {
+        OscPacket::Message(mut msg) => {
+            msg.args.push(OscType::Int(sequence));
+            OscPacket::Message(msg)
+        }
+        OscPacket::Bundle(mut bundle) => {
+            bundle.content = bundle
+                .content
+                .into_iter()
+                .map(|packet| with_sequence(packet, sequence))
+                .collect();
+            OscPacket::Bundle(bundle)
+        }
+    }
+}
+
+/// Adds a log entry for every message in the packet, recording it too while
+/// a recording is active.
+fn log_sent(state: &mut AppState, packet: &OscPacket, dests: &[SocketAddr], dry_run: bool) {
+    match packet {
+        OscPacket::Message(msg) => {
+            if let Some(recording) = &mut state.recording
+                && recording.active
+            {
+                recording.record(msg);
+            }
+            state.push_log(LogEntry {
+                at: Instant::now(),
+                time: Local::now(),
+                direction: Direction::Sent,
+                dry_run,
+                address: msg.addr.clone(),
+                value: msg.args.first().cloned().unwrap_or(OscType::Nil),
+                dests: dests.to_vec(),
+            });
+        }
+        OscPacket::Bundle(bundle) => {
+            for packet in &bundle.content {
+                log_sent(state, packet, dests, dry_run);
+            }
+        }
+    }
+}
+
+pub fn write_log_csv(path: &Path, entries: &[LogEntry]) -> io::Result<()> {
+    let mut out = io::BufWriter::new(fs::File::create(path)?);
+    writeln!(out, "timestamp,direction,address,value,peer")?;
+    for entry in entries {
+        let dests: Vec<String> = entry.dests.iter().map(SocketAddr::to_string).collect();
+        writeln!(
+            out,
+            "{},{},{},{},{}",
+            entry.time.to_rfc3339_opts(SecondsFormat::Millis, false),
+            match (entry.direction, entry.dry_run) {
+                (Direction::Sent, false) => "sent",
+                (Direction::Sent, true) => "sent (dry)",
+                (Direction::Received, _) => "received",
+            },
+            csv_field(&entry.address),
+            csv_field(&format_arg(&entry.value)),
+            csv_field(&dests.join(" ")),
+        )?;
+    }
+    out.flush()
+}
+
+/// Formats bytes like `hexdump -C`: offset, 16 hex bytes, then the printable
+/// ASCII characters.
+pub fn hex_dump(bytes: &[u8]) -> String {
+    let mut out = String::new();
+    for (row, chunk) in bytes.chunks(16).enumerate() {
+        let hex: Vec<String> = chunk.iter().map(|b| format!("{b:02x}")).collect();
+        let ascii: String = chunk
+            .iter()
+            .map(|&b| {
+                if b.is_ascii_graphic() || b == b' ' {
+                    b as char
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        if row > 0 {
+            out.push('\n');
+        }
+        out.push_str(&format!(
+            "{:08x}  {:<47}  |{ascii}|",
+            row * 16,
+            hex.join(" ")
+        ));
+    }
+    out
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+pub fn format_arg(arg: &OscType) -> String {
+    match arg {
+        OscType::Int(v) => v.to_string(),
+        OscType::Float(v) => format!("{v:.3}"),
+        OscType::Bool(v) => v.to_string(),
+        OscType::String(v) => format!("{v:?}"),
+        other => format!("{other:?}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rosc::decoder;
+
+    /// Captures every packet instead of sending it.
+    #[derive(Default)]
+    struct MockSink {
+        sent: Mutex<Vec<(Vec<u8>, SocketAddr)>>,
+    }
+
+    impl Sink for MockSink {
+        fn send(&self, bytes: &[u8], addr: SocketAddr) -> io::Result<usize> {
+            self.sent.lock().unwrap().push((bytes.to_vec(), addr));
+            Ok(bytes.len())
+        }
+    }
+
+    impl Sink for &MockSink {
+        fn send(&self, bytes: &[u8], addr: SocketAddr) -> io::Result<usize> {
+            (**self).send(bytes, addr)
+        }
+    }
+
+    impl MockSink {
+        fn packets(&self) -> Vec<(OscPacket, SocketAddr)> {
+            self.sent
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(bytes, addr)| (decoder::decode_udp(bytes).unwrap().1, *addr))
+                .collect()
+        }
+    }
+
+    fn message(addr: &str, arg: OscType) -> OscPacket {
+        OscPacket::Message(OscMessage {
+            addr: addr.to_string(),
+            args: vec![arg],
+        })
+    }
+
+    #[test]
+    fn click_sends_press_then_release_to_every_port() {
+        let settings = Settings {
+            hold_ms: 1,
+            dest_ports: vec![9000, 9002],
+            address: "/input/Jump".to_string(),
+            arg_kind: ArgKind::Int,
+            ..Settings::default()
+        };
+        let shared = Shared::new(settings.clone());
+        let sink = MockSink::default();
+
+        send_click(&shared, &sink, &settings.destinations(), &settings).unwrap();
+
+        let [a, b] = settings.destinations()[..] else {
+            unreachable!()
+        };
+        let press = message("/input/Jump", OscType::Int(1));
+        let release = message("/input/Jump", OscType::Int(0));
+        assert_eq!(
+            sink.packets(),
+            [
+                (press.clone(), a),
+                (press, b),
+                (release.clone(), a),
+                (release, b)
+            ]
+        );
+        let state = shared.state.lock().unwrap();
+        assert_eq!(state.packets_sent, 4);
+        assert_eq!(state.log.len(), 2);
+    }
+
+    struct FailingSink;
+
+    impl Sink for FailingSink {
+        fn send(&self, _bytes: &[u8], _addr: SocketAddr) -> io::Result<usize> {
+            Err(io::ErrorKind::WouldBlock.into())
+        }
+    }
+
+    /// Fails the first `failures` sends, then succeeds.
+    struct FlakySink {
+        failures: AtomicU32,
+    }
+
+    impl Sink for FlakySink {
+        fn send(&self, bytes: &[u8], _addr: SocketAddr) -> io::Result<usize> {
+            let left = self.failures.load(Ordering::SeqCst);
+            if left > 0 {
+                self.failures.store(left - 1, Ordering::SeqCst);
+                return Err(io::ErrorKind::WouldBlock.into());
+            }
+            Ok(bytes.len())
+        }
+    }
+
+    #[test]
+    fn failed_sends_are_retried_up_to_the_limit() {
+        let settings = Settings {
+            send_retries: 2,
+            ..Settings::default()
+        };
+        let shared = Shared::new(settings.clone());
+        let dests = settings.destinations();
+        let packet = settings.click_packet(true);
+
+        let sink = FlakySink {
+            failures: AtomicU32::new(2),
+        };
+        send_packet(&shared, &sink, &dests, &packet).unwrap();
+
+        let sink = FlakySink {
+            failures: AtomicU32::new(3),
+        };
+        assert!(send_packet(&shared, &sink, &dests, &packet).is_err());
+
+        let state = shared.state.lock().unwrap();
+        assert_eq!(state.retries, 4);
+        assert_eq!(state.failed_sends, 1);
+        assert_eq!(state.packets_sent, 1);
+    }
+
+    #[test]
+    fn repeated_send_failures_rebind_the_socket() {
+        let settings = Settings {
+            interval_ms: 10,
+            hold_ms: 10,
+            ..Settings::default()
+        };
+        let shared = Shared::new(settings);
+        let binds = AtomicU32::new(0);
+
+        thread::scope(|scope| {
+            scope.spawn(|| {
+                sender_loop(&shared, |_| {
+                    binds.fetch_add(1, Ordering::SeqCst);
+                    Some((FailingSink, Settings::default().socket_config()))
+                })
+            });
+            shared.update(|s| s.set_sending(true));
+            let deadline = Instant::now() + Duration::from_secs(2);
+            while binds.load(Ordering::SeqCst) < 3 {
+                assert!(Instant::now() < deadline, "socket was never rebound");
+                thread::sleep(Duration::from_millis(5));
+            }
+            shared.request_shutdown();
+        });
+
+        let state = shared.state.lock().unwrap();
+        assert!(state.send_failures >= 2 * MAX_SEND_FAILURES);
+        assert_eq!(state.packets_sent, 0);
+    }
+
+    #[test]
+    fn every_stop_is_followed_by_one_release() {
+        let settings = Settings {
+            interval_ms: 1000,
+            hold_ms: 200,
+            ..Settings::default()
+        };
+        let shared = Shared::new(settings);
+        let sink = MockSink::default();
+        let wait_for = |count| {
+            let deadline = Instant::now() + Duration::from_secs(2);
+            while sink.sent.lock().unwrap().len() < count {
+                assert!(
+                    Instant::now() < deadline,
+                    "timed out waiting for packet {count}"
+                );
+                thread::sleep(Duration::from_millis(5));
+            }
+        };
+
+        thread::scope(|scope| {
+            scope.spawn(|| {
+                sender_loop(&shared, |_| {
+                    Some((&sink, Settings::default().socket_config()))
+                })
+            });
+
+            // Stopped during the rest after a click: released again, in case
+            // the click's release was lost.
+            shared.update(|s| s.set_sending(true));
+            wait_for(2);
+            shared.update(|s| s.set_sending(false));
+            wait_for(3);
+
+            // Stopped while the button is still held down: released right
+            // away instead of after the hold, and only once.
+            shared.update(|s| s.set_sending(true));
+            wait_for(4);
+            shared.update(|s| s.set_sending(false));
+            let stopped = Instant::now();
+            wait_for(5);
+            assert!(stopped.elapsed() < Duration::from_millis(150));
+
+            thread::sleep(Duration::from_millis(100));
+            shared.request_shutdown();
+        });
+
+        let press = message(DEFAULT_ADDRESS, OscType::Float(1.0));
+        let release = message(DEFAULT_ADDRESS, OscType::Float(0.0));
+        let packets: Vec<OscPacket> = sink.packets().into_iter().map(|(p, _)| p).collect();
+        assert_eq!(
+            packets,
+            [
+                press.clone(),
+                release.clone(),
+                release.clone(),
+                press,
+                release
+            ]
+        );
+    }
+
+    #[test]
+    fn clicks_cycle_through_the_valid_addresses() {
+        let settings = Settings {
+            hold_ms: 1,
+            address_cycle: vec![
+                "/input/A".to_string(),
+                "bad".to_string(),
+                "/input/B".to_string(),
+            ],
+            ..Settings::default()
+        };
+        let shared = Shared::new(settings.clone());
+        let sink = MockSink::default();
+        let dests = settings.destinations();
+
+        for _ in 0..3 {
+            send_click(&shared, &sink, &dests, &settings).unwrap();
+        }
+        send_release(&shared, &sink, &dests, &settings).unwrap();
+
+        let addresses: Vec<String> = sink
+            .packets()
+            .into_iter()
+            .map(|(packet, _)| match packet {
+                OscPacket::Message(msg) => msg.addr,
+                OscPacket::Bundle(_) => unreachable!(),
+            })
+            .collect();
+        assert_eq!(
+            addresses,
+            [
+                "/input/A", "/input/A", "/input/B", "/input/B", "/input/A", "/input/A", "/input/A"
+            ]
+        );
+    }
+
+    #[test]
+    fn weighted_cycle_picks_only_weighted_addresses() {
+        let settings = Settings {
+            address_cycle: vec![
+                "/input/A".to_string(),
+                "bad".to_string(),
+                "/input/B".to_string(),
+                "/input/C".to_string(),
+            ],
+            cycle_random: true,
+            cycle_weights: vec![0, 5, 3],
+            ..Settings::default()
+        };
+        let shared = Shared::new(settings.clone());
+        let mut state = shared.state.lock().unwrap();
+        for _ in 0..50 {
+            let address = state.next_cycle_address().unwrap();
+            assert!(address == "/input/B" || address == "/input/C", "{address}");
+            assert_eq!(state.current_cycle_address(), Some(address));
+        }
+
+        state.settings.cycle_weights = vec![0, 0, 0, 0];
+        state.cycle_index = 0;
+        assert!(state.settings.invalid_cycle_weights());
+        let picks: Vec<_> = (0..3).filter_map(|_| state.next_cycle_address()).collect();
+        assert_eq!(picks, ["/input/A", "/input/B", "/input/C"]);
+    }
+
+    #[test]
+    fn send_rate_counts_per_second_within_the_window() {
+        let mut rate = RateHistory::new();
+        let origin = rate.origin;
+        let at = |ms| origin + Duration::from_millis(ms);
+        for ms in [100, 200, 900, 2500] {
+            rate.record(at(ms));
+        }
+
+        let points = rate.points(at(3100));
+        assert_eq!(points, [[-3.0, 3.0], [-2.0, 0.0], [-1.0, 1.0]]);
+
+        // Seconds falling out of the window are dropped.
+        rate.record(at((RATE_WINDOW_SECS + 1) * 1000));
+        assert_eq!(rate.buckets, [(2, 1), (RATE_WINDOW_SECS + 1, 1)]);
+    }
+
+    #[test]
+    fn toggle_mode_flips_without_releasing() {
+        let settings = Settings {
+            input_mode: InputMode::Toggle,
+            ..Settings::default()
+        };
+        let shared = Shared::new(settings.clone());
+        let sink = MockSink::default();
+        let dests = settings.destinations();
+
+        for _ in 0..3 {
+            send_input(&shared, &sink, &dests, &settings).unwrap();
+        }
+        shared.update(|s| s.set_sending(true));
+        send_input(&shared, &sink, &dests, &settings).unwrap();
+
+        let packets: Vec<OscPacket> = sink.packets().into_iter().map(|(p, _)| p).collect();
+        let press = message(DEFAULT_ADDRESS, OscType::Float(1.0));
+        let release = message(DEFAULT_ADDRESS, OscType::Float(0.0));
+        assert_eq!(packets, [press.clone(), release.clone(), press, release]);
+    }
+
+    #[test]
+    fn round_robin_sends_each_click_to_the_next_port() {
+        let settings = Settings {
+            hold_ms: 1,
+            dest_ports: vec![9000, 9002, 9004],
+            destination_mode: DestinationMode::RoundRobin,
+            ..Settings::default()
+        };
+        let shared = Shared::new(settings.clone());
+        let sink = MockSink::default();
+        let dests = settings.destinations();
+
+        for _ in 0..4 {
+            send_click(&shared, &sink, &dests, &settings).unwrap();
+        }
+        send_release(&shared, &sink, &dests, &settings).unwrap();
+
+        let ports: Vec<u16> = sink
+            .sent
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, addr)| addr.port())
+            .collect();
+        assert_eq!(
+            ports,
+            [9000, 9000, 9002, 9002, 9004, 9004, 9000, 9000, 9000]
+        );
+    }
+
+    #[test]
+    fn sequence_counts_packets_and_restarts_with_sending() {
+        let settings = Settings {
+            hold_ms: 1,
+            dest_ports: vec![9000, 9002],
+            append_sequence: true,
+            ..Settings::default()
+        };
+        let shared = Shared::new(settings.clone());
+        let sink = MockSink::default();
+        let dests = settings.destinations();
+        let sequences = |sink: &MockSink| -> Vec<OscType> {
+            sink.packets()
+                .into_iter()
+                .map(|(packet, _)| match packet {
+                    OscPacket::Message(mut msg) => {
+                        assert_eq!(msg.args.len(), 2);
+                        msg.args.pop().unwrap()
+                    }
+                    OscPacket::Bundle(_) => unreachable!(),
+                })
+                .collect()
+        };
+
+        send_click(&shared, &sink, &dests, &settings).unwrap();
+        assert_eq!(sequences(&sink), [0, 0, 1, 1].map(OscType::Int));
+
+        shared.update(|s| s.set_sending(true));
+        let sink = MockSink::default();
+        send_release(&shared, &sink, &dests, &settings).unwrap();
+        assert_eq!(sequences(&sink), [0, 0].map(OscType::Int));
+    }
+
+    #[test]
+    fn bundle_entries_share_the_arg_type() {
+        let settings = Settings {
+            arg_kind: ArgKind::Bool,
+            bundle: vec![BundleEntry {
+                address: "/avatar/parameters/Wave".to_string(),
+                on_value: 1.0,
+                off_value: 0.0,
+            }],
+            ..Settings::default()
+        };
+        let shared = Shared::new(settings.clone());
+        let sink = MockSink::default();
+
+        send_release(&shared, &sink, &settings.destinations(), &settings).unwrap();
+
+        let [(OscPacket::Bundle(bundle), _)] = &sink.packets()[..] else {
+            panic!("expected one bundle");
+        };
+        assert_eq!(bundle.timetag, OSC_IMMEDIATELY);
+        assert_eq!(
+            bundle.content,
+            [
+                message(DEFAULT_ADDRESS, OscType::Bool(false)),
+                message("/avatar/parameters/Wave", OscType::Bool(false)),
+            ]
+        );
+    }
+
+    #[test]
+    fn emergency_stop_releases_immediately() {
+        let receiver = UdpSocket::bind("127.0.0.1:0").unwrap();
+        receiver
+            .set_read_timeout(Some(Duration::from_secs(1)))
+            .unwrap();
+        let settings = Settings {
+            dest_ports: vec![receiver.local_addr().unwrap().port()],
+            ..Settings::default()
+        };
+        let shared = Shared::new(settings.clone());
+        shared.update(|s| s.set_sending(true));
+        shared.request_click();
+
+        shared.emergency_stop();
+
+        let mut buf = [0; decoder::MTU];
+        let len = receiver.recv(&mut buf).unwrap();
+        let (_, decoded) = decoder::decode_udp(&buf[..len]).unwrap();
+        assert_eq!(decoded, settings.click_packet(false));
+        let state = shared.state.lock().unwrap();
+        assert!(!state.is_sending);
+        assert!(state.stopped_at.is_some());
+        assert_eq!(shared.pending_clicks.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn emergency_release_uses_the_socket_options() {
+        // Without SO_BROADCAST the OS refuses a broadcast destination.
+        let settings = Settings {
+            dest_ip: "127.255.255.255".parse().unwrap(),
+            broadcast: true,
+            ..Settings::default()
+        };
+        let shared = Shared::new(settings);
+        shared.update(|s| s.set_sending(true));
+
+        shared.emergency_stop();
+
+        let state = shared.state.lock().unwrap();
+        assert_eq!(state.last_error, None);
+        assert_eq!(state.packets_sent, 1);
+        assert!(state.halted);
+    }
+
+    #[test]
+    fn emergency_stop_cuts_the_hold_short_without_another_release() {
+        let settings = Settings {
+            interval_ms: 5000,
+            hold_ms: 2000,
+            ..Settings::default()
+        };
+        let shared = Shared::new(settings);
+        let sink = MockSink::default();
+
+        let started = Instant::now();
+        thread::scope(|scope| {
+            scope.spawn(|| {
+                sender_loop(&shared, |_| {
+                    Some((&sink, Settings::default().socket_config()))
+                })
+            });
+            shared.update(|s| s.set_sending(true));
+            while sink.sent.lock().unwrap().is_empty() {
+                thread::sleep(Duration::from_millis(5));
+            }
+            // The emergency release itself goes out on a real socket.
+            shared.emergency_stop();
+            thread::sleep(Duration::from_millis(100));
+            shared.request_shutdown();
+        });
+
+        assert!(started.elapsed() < Duration::from_secs(1));
+        let press = message(DEFAULT_ADDRESS, OscType::Float(1.0));
+        let packets: Vec<OscPacket> = sink.packets().into_iter().map(|(p, _)| p).collect();
+        assert_eq!(packets, [press]);
+    }
+
+    #[test]
+    fn notes_are_dropped_while_paused_but_a_held_press_is_released() {
+        let receiver = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let settings = Settings {
+            dest_ports: vec![receiver.local_addr().unwrap().port()],
+            ..Settings::default()
+        };
+        let shared = Shared::new(settings);
+        shared.update(|s| s.set_sending(true));
+
+        shared.send_now(true);
+        shared.update(|s| s.paused = true);
+        shared.send_now(true);
+        assert_eq!(shared.state.lock().unwrap().packets_sent, 1);
+
+        shared.send_now(false);
+        shared.send_now(false);
+        let state = shared.state.lock().unwrap();
+        assert_eq!(state.packets_sent, 2);
+        assert!(!state.held);
+    }
+
+    #[test]
+    fn starting_to_an_untrusted_machine_waits_for_confirmation() {
+        let settings = Settings {
+            dest_ip: "192.168.1.20".parse().unwrap(),
+            ..Settings::default()
+        };
+        let shared = Shared::new(settings);
+        let mut state = shared.state.lock().unwrap();
+
+        assert!(!state.request_start());
+        assert_eq!(state.confirm_start.as_deref(), Some("192.168.1.20"));
+
+        state.confirm_start = None;
+        state.trusted_ips.push("192.168.1.20".parse().unwrap());
+        assert!(state.request_start());
+        assert_eq!(state.confirm_start, None);
+
+        state.set_sending(false);
+        state.trusted_ips.clear();
+        state.settings.dest_ip = Ipv4Addr::LOCALHOST.into();
+        assert!(state.request_start());
+        state.set_sending(false);
+
+        // A hostname asks by name even while `dest_ip` is still loopback.
+        state.settings.dest_host = "gaming-pc.local".into();
+        assert!(!state.request_start());
+        assert_eq!(state.confirm_start.as_deref(), Some("gaming-pc.local"));
+        state.trusted_hosts.push("Gaming-PC.local".into());
+        assert!(state.request_start());
+        state.set_sending(false);
+        state.settings.dest_host = "localhost".into();
+        assert!(state.request_start());
+        state.set_sending(false);
+
+        // One-off sends are refused the same way until sending is confirmed.
+        state.settings.dest_host = "other-pc.local".into();
+        assert!(!state.allows_one_off());
+        assert!(
+            state
+                .last_error
+                .as_deref()
+                .unwrap()
+                .contains("other-pc.local")
+        );
+        state.set_sending(true);
+        assert!(state.allows_one_off());
+    }
+
+    #[test]
+    fn quiet_sends_leave_the_click_stream_alone() {
+        let settings = Settings {
+            append_sequence: true,
+            ..Settings::default()
+        };
+        let shared = Shared::new(settings);
+        shared.update(|s| s.recording = Some(Recording::new()));
+        let sink = MockSink::default();
+        let heartbeat = message("/osc_sender/heartbeat", OscType::Bool(true));
+
+        send_quiet(
+            &shared,
+            &sink,
+            &Settings::default().destinations(),
+            &heartbeat,
+        )
+        .unwrap();
+
+        let (packet, _) = sink.packets().pop().unwrap();
+        assert_eq!(packet, heartbeat);
+        let state = shared.state.lock().unwrap();
+        assert_eq!((state.sequence, state.packets_sent), (0, 0));
+        assert!(state.log.is_empty());
+        assert!(state.recording.as_ref().unwrap().events.is_empty());
+    }
+
+    #[test]
+    fn bundle_delay_timetag_round_trips() {
+        let receiver = UdpSocket::bind("127.0.0.1:0").unwrap();
+        receiver
+            .set_read_timeout(Some(Duration::from_secs(1)))
+            .unwrap();
+        let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let settings = Settings {
+            bundle_delay_ms: 250,
+            ..Settings::default()
+        };
+        let shared = Shared::new(settings.clone());
+
+        let before = SystemTime::now();
+        let packet = settings.click_packet(true);
+        send_packet(&shared, &sender, &[receiver.local_addr().unwrap()], &packet).unwrap();
+
+        let mut buf = [0; decoder::MTU];
+        let len = receiver.recv(&mut buf).unwrap();
+        let (_, decoded) = decoder::decode_udp(&buf[..len]).unwrap();
+        let (OscPacket::Bundle(sent), OscPacket::Bundle(received)) = (&packet, &decoded) else {
+            panic!("expected a bundle, got {decoded:?}");
+        };
+        assert_eq!(received.timetag, sent.timetag);
+        assert_eq!(received.content, sent.content);
+
+        // Conversions are lossy by a few nanoseconds.
+        let at = SystemTime::from(received.timetag) + Duration::from_micros(1);
+        assert!(at >= before + Duration::from_millis(250));
+        assert!(at <= SystemTime::now() + Duration::from_millis(251));
+    }
+
+    #[test]
+    fn hex_dump_shows_offset_hex_and_ascii() {
+        let bytes: Vec<u8> = b"/input/UseRight\0,f\0\0".to_vec();
+        assert_eq!(
+            hex_dump(&bytes),
+            "00000000  2f 69 6e 70 75 74 2f 55 73 65 52 69 67 68 74 00  |/input/UseRight.|\n\
+             00000010  2c 66 00 00                                      |,f..|"
+        );
+    }
+
+    #[test]
+    fn sweep_follows_the_waveform_between_the_bounds() {
+        let settings = Settings {
+            sweep_min: -1.0,
+            sweep_max: 1.0,
+            sweep_period_ms: 1000,
+            ..Settings::default()
+        };
+        let at = |ms| settings.sweep_value(Duration::from_millis(ms));
+        assert_eq!(
+            [at(0), at(250), at(500), at(750), at(1000)],
+            [-1.0, 0.0, 1.0, 0.0, -1.0]
+        );
+
+        let settings = Settings {
+            waveform: Waveform::Ramp,
+            ..settings
+        };
+        let at = |ms| settings.sweep_value(Duration::from_millis(ms));
+        assert_eq!([at(0), at(500), at(1250)], [-1.0, 0.0, -0.5]);
+
+        let settings = Settings {
+            waveform: Waveform::Square,
+            ..settings
+        };
+        let at = |ms| settings.sweep_value(Duration::from_millis(ms));
+        assert_eq!([at(0), at(499), at(500), at(999)], [-1.0, -1.0, 1.0, 1.0]);
+
+        let settings = Settings {
+            waveform: Waveform::Sine,
+            ..settings
+        };
+        let at = |ms| settings.sweep_value(Duration::from_millis(ms));
+        assert_eq!([at(0), at(500)], [-1.0, 1.0]);
+        assert!(at(250).abs() < 1e-6);
+    }
+
+    #[test]
+    fn sweep_ends_on_the_off_value_at_the_sweep_address() {
+        let settings = Settings {
+            sweep_mode: true,
+            interval_ms: 1000,
+            off_value: 0.25,
+            arg_kind: ArgKind::Int,
+            release_address: "/input/Release".into(),
+            ..Settings::default()
+        };
+        let shared = Shared::new(settings);
+        let sink = MockSink::default();
+
+        thread::scope(|scope| {
+            scope.spawn(|| {
+                sender_loop(&shared, |_| {
+                    Some((&sink, Settings::default().socket_config()))
+                })
+            });
+            shared.update(|s| s.set_sending(true));
+            while sink.sent.lock().unwrap().is_empty() {
+                thread::sleep(Duration::from_millis(5));
+            }
+            shared.update(|s| s.set_sending(false));
+            while sink.sent.lock().unwrap().len() < 2 {
+                thread::sleep(Duration::from_millis(5));
+            }
+            shared.request_shutdown();
+        });
+
+        let (end, _) = sink.packets().pop().unwrap();
+        assert_eq!(end, message(DEFAULT_ADDRESS, OscType::Float(0.25)));
+    }
+
+    #[test]
+    fn text_packet_sends_the_string_and_optional_bool() {
+        let settings = Settings {
+            text_mode: true,
+            address: CHATBOX_ADDRESS.to_string(),
+            text: "hello\nworld".to_string(),
+            ..Settings::default()
+        };
+        let OscPacket::Message(msg) = settings.text_packet() else {
+            panic!("expected a single message");
+        };
+        assert_eq!(msg.addr, CHATBOX_ADDRESS);
+        assert_eq!(
+            msg.args,
+            [
+                OscType::String("hello\nworld".to_string()),
+                OscType::Bool(true)
+            ]
+        );
+        assert_eq!(settings.packets_per_click(), 1);
+
+        let settings = Settings {
+            text_send_bool: false,
+            ..settings
+        };
+        let OscPacket::Message(msg) = settings.text_packet() else {
+            panic!("expected a single message");
+        };
+        assert_eq!(msg.args, [OscType::String("hello\nworld".to_string())]);
+    }
+
+    #[test]
+    fn gaussian_jitter_stays_within_three_standard_deviations() {
+        let settings = Settings {
+            randomize_interval: true,
+            jitter: Jitter::Gaussian,
+            interval_ms: 100,
+            jitter_std_dev_ms: 20,
+            ..Settings::default()
+        };
+        assert_eq!(settings.interval_bounds(), (40, 160));
+        let samples: Vec<u64> = (0..1000).map(|_| settings.next_interval_ms()).collect();
+        assert!(samples.iter().all(|ms| (40..=160).contains(ms)));
+        let mean = samples.iter().sum::<u64>() as f64 / samples.len() as f64;
+        assert!((mean - 100.0).abs() < 5.0, "mean {mean}");
+
+        // The lower bound never drops below the shortest interval.
+        let settings = Settings {
+            interval_ms: 20,
+            ..settings
+        };
+        assert_eq!(settings.interval_bounds(), (*INTERVAL_RANGE.start(), 80));
+
+        let settings = Settings {
+            jitter_std_dev_ms: 0,
+            ..settings
+        };
+        assert_eq!(settings.next_interval_ms(), 20);
+
+        // An interval below the range with no spread stays a valid range.
+        let settings = Settings {
+            interval_ms: 5,
+            ..settings
+        };
+        let floor = *INTERVAL_RANGE.start();
+        assert_eq!(settings.interval_bounds(), (floor, floor));
+        assert_eq!(settings.next_interval_ms(), floor);
+        assert!(rest_after_click(&settings, 5, 0, false) >= Duration::from_millis(floor));
+
+        let settings = Settings {
+            interval_ms: u64::MAX,
+            jitter_std_dev_ms: u64::MAX,
+            ..settings
+        };
+        assert_eq!(settings.interval_bounds(), (floor, u64::MAX));
+    }
+
+    #[test]
+    fn interval_ramps_linearly_then_holds() {
+        let settings = Settings {
+            ramp_enabled: true,
+            ramp_start_ms: 1000,
+            ramp_end_ms: 200,
+            ramp_secs: 10,
+            ..Settings::default()
+        };
+        let at = |secs| settings.ramp_interval_ms(Duration::from_secs(secs));
+        assert_eq!([at(0), at(5), at(10), at(60)], [1000, 600, 200, 200]);
+
+        let mut state = Shared::new(settings.clone()).state.into_inner().unwrap();
+        assert_eq!(state.effective_settings().interval_ms, 1000);
+        state.started_at = Some(Instant::now() - Duration::from_secs(5));
+        assert!((590..=600).contains(&state.effective_settings().interval_ms));
+
+        let settings = Settings {
+            ramp_secs: 0,
+            ..settings
+        };
+        assert_eq!(settings.ramp_interval_ms(Duration::ZERO), 200);
+    }
+
+    #[test]
+    fn run_estimate_counts_every_packet_and_byte() {
+        let settings = Settings {
+            click_limit: 10,
+            dest_ports: vec![9000, 9002],
+            ..Settings::default()
+        };
+        // "/input/UseRight" pads to 16 bytes, ",f" to 4, plus a 4-byte float.
+        assert_eq!(settings.run_estimate(), Some((40, 40 * 24)));
+
+        let settings = Settings {
+            destination_mode: DestinationMode::RoundRobin,
+            input_mode: InputMode::Toggle,
+            ..settings
+        };
+        assert_eq!(settings.run_estimate(), Some((10, 10 * 24)));
+
+        let settings = Settings {
+            click_limit: 0,
+            ..settings
+        };
+        assert_eq!(settings.run_estimate(), None);
+    }
+
+    #[test]
+    fn nudging_a_port_clamps_to_the_valid_range() {
+        assert_eq!(nudge_port(9000, 2), Some(9002));
+        assert_eq!(nudge_port(9000, -2), Some(8998));
+        assert_eq!(nudge_port(1, -2), Some(0));
+        assert_eq!(nudge_port(65534, 2), Some(65535));
+        // Already at a bound, so nothing changes and nothing is pushed.
+        assert_eq!(nudge_port(0, -2), None);
+        assert_eq!(nudge_port(65535, 2), None);
+        assert_eq!(nudge_port(9000, 0), None);
+    }
+
+    #[test]
+    fn rate_cap_spaces_clicks_by_packet_count() {
+        let settings = Settings {
+            interval_ms: 10,
+            hold_ms: 5,
+            dest_ports: vec![9000, 9002],
+            max_packets_per_sec: 100,
+            ..Settings::default()
+        };
+        // Press and release to two ports is four packets, so 40ms per click.
+        assert_eq!(settings.min_click_period_ms(), 40);
+        assert!(settings.rate_limited());
+
+        let settings = Settings {
+            interval_ms: 40,
+            ..settings
+        };
+        assert!(!settings.rate_limited());
+
+        let settings = Settings {
+            max_packets_per_sec: 0,
+            ..settings
+        };
+        assert_eq!(settings.min_click_period_ms(), 0);
+    }
+
+    #[test]
+    fn schedule_window_can_span_midnight() {
+        let evening = Schedule {
+            enabled: true,
+            start_min: 20 * 60,
+            stop_min: 20 * 60 + 30,
+        };
+        assert!(!evening.contains(20 * 60 - 1));
+        assert!(evening.contains(20 * 60));
+        assert!(!evening.contains(20 * 60 + 30));
+
+        let overnight = Schedule {
+            start_min: 23 * 60,
+            stop_min: 60,
+            ..evening
+        };
+        assert!(overnight.contains(23 * 60 + 30));
+        assert!(overnight.contains(30));
+        assert!(!overnight.contains(60));
+        assert!(!overnight.contains(12 * 60));
+    }
+
+    #[test]
+    fn socket_options_are_applied_on_open() {
+        let config = Settings {
+            bind_ip: Some(Ipv4Addr::LOCALHOST.into()),
+            send_buffer_bytes: 64 * 1024,
+            reuse_address: true,
+            broadcast: true,
+            ..Settings::default()
+        }
+        .socket_config();
+        let socket = config.open().unwrap();
+        let socket = socket2::SockRef::from(&socket);
+        assert!(socket.reuse_address().unwrap());
+        assert!(socket.broadcast().unwrap());
+        // The OS may round the size up (Linux doubles it).
+        assert!(socket.send_buffer_size().unwrap() >= 64 * 1024);
+    }
+
+    #[test]
+    fn multicast_options_need_a_multicast_group() {
+        let group = Settings {
+            dest_ip: "239.1.2.3".parse().unwrap(),
+            multicast: true,
+            multicast_ttl: 4,
+            multicast_loop: false,
+            ..Settings::default()
+        };
+        assert!(!group.invalid_multicast());
+        let socket = group.socket_config().open().unwrap();
+        let socket = socket2::SockRef::from(&socket);
+        assert_eq!(socket.multicast_ttl_v4().unwrap(), 4);
+        assert!(!socket.multicast_loop_v4().unwrap());
+
+        for ip in ["127.0.0.1", "240.0.0.1", "ff02::1"] {
+            let unicast = Settings {
+                dest_ip: ip.parse().unwrap(),
+                ..group.clone()
+            };
+            assert!(unicast.invalid_multicast(), "{ip}");
+            assert_eq!(unicast.socket_config().multicast, None);
+        }
+    }
+
+    #[test]
+    fn destinations_and_bind_address_follow_the_ip_family() {
+        let v4 = Settings {
+            dest_ports: vec![9000, 9002],
+            ..Settings::default()
+        };
+        let dests: Vec<String> = v4.destinations().iter().map(ToString::to_string).collect();
+        assert_eq!(dests, ["127.0.0.1:9000", "127.0.0.1:9002"]);
+        assert_eq!(v4.local_bind_ip().to_string(), "0.0.0.0");
+
+        let v6 = Settings {
+            dest_ip: "::1".parse().unwrap(),
+            ..v4
+        };
+        let dests: Vec<String> = v6.destinations().iter().map(ToString::to_string).collect();
+        assert_eq!(dests, ["[::1]:9000", "[::1]:9002"]);
+        assert_eq!(v6.local_bind_ip().to_string(), "::");
+
+        let bound = Settings {
+            bind_ip: Some("192.168.1.2".parse().unwrap()),
+            ..v6
+        };
+        assert_eq!(bound.local_bind_ip().to_string(), "192.168.1.2");
+    }
+
+    #[test]
+    fn lifetime_runtime_adds_up_sessions() {
+        let shared = Shared::new(Settings::default());
+        let mut state = shared.state.lock().unwrap();
+        assert_eq!(state.lifetime_runtime(), Duration::ZERO);
+
+        state.set_sending(true);
+        state.started_at = Some(Instant::now() - Duration::from_secs(2));
+        state.set_sending(false);
+        // Stopping twice must not count the session again.
+        state.set_sending(false);
+        let first = state.lifetime_runtime();
+        assert!(first >= Duration::from_secs(2) && first < Duration::from_secs(3));
+
+        state.set_sending(true);
+        state.started_at = Some(Instant::now() - Duration::from_secs(5));
+        assert!(state.lifetime_runtime() >= first + Duration::from_secs(5));
+    }
+
+    #[test]
+    fn hold_as_long_as_the_interval_is_flagged() {
+        let settings = Settings {
+            interval_ms: 200,
+            hold_ms: 199,
+            ..Settings::default()
+        };
+        assert!(!settings.hold_exceeds_interval());
+        let held = Settings {
+            hold_ms: 200,
+            ..settings.clone()
+        };
+        assert!(held.hold_exceeds_interval());
+        assert!(
+            !Settings {
+                input_mode: InputMode::Toggle,
+                ..held
+            }
+            .hold_exceeds_interval()
+        );
+        assert!(
+            Settings {
+                randomize_hold: true,
+                hold_min_ms: 50,
+                hold_max_ms: 250,
+                ..settings.clone()
+            }
+            .hold_exceeds_interval()
+        );
+        assert!(
+            Settings {
+                ramp_enabled: true,
+                ramp_start_ms: 1000,
+                ramp_end_ms: 150,
+                ..settings
+            }
+            .hold_exceeds_interval()
+        );
+    }
+
+    #[test]
+    fn release_goes_to_the_release_address_when_set() {
+        let addr = |packet| match packet {
+            OscPacket::Message(msg) => msg.addr,
+            OscPacket::Bundle(_) => unreachable!(),
+        };
+        let settings = Settings {
+            release_address: "/input/Reset".to_string(),
+            ..Settings::default()
+        };
+        assert_eq!(addr(settings.click_packet(true)), DEFAULT_ADDRESS);
+        assert_eq!(addr(settings.click_packet(false)), "/input/Reset");
+
+        for release_address in ["", "bad"] {
+            let settings = Settings {
+                release_address: release_address.to_string(),
+                ..settings.clone()
+            };
+            assert_eq!(addr(settings.click_packet(false)), DEFAULT_ADDRESS);
+        }
+    }
+
+    #[test]
+    fn single_immediate_message_is_not_bundled() {
+        let packet = Settings::default().click_packet(true);
+        assert!(matches!(packet, OscPacket::Message(msg) if msg.addr == DEFAULT_ADDRESS));
+    }
+}
@@ -0,0 +1,73 @@
+//! Minimal OSCQuery client for finding the port VRChat receives OSC on.
+
+use std::io::{Read, Write};
+use std::net::{IpAddr, SocketAddr, TcpStream};
+use std::time::{Duration, Instant};
+
+use mdns_sd::{ServiceDaemon, ServiceEvent};
+
+const SERVICE_TYPE: &str = "_oscjson._tcp.local.";
+const VRCHAT_PREFIX: &str = "VRChat-Client";
+const HTTP_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Browses for VRChat's OSCQuery service and asks it for its OSC receive
+/// port. Blocks for up to `timeout`, so run it off the UI thread.
+pub fn detect_vrchat_port(timeout: Duration) -> Result<u16, String> {
+    let daemon = ServiceDaemon::new().map_err(|e| format!("mDNS unavailable: {e}"))?;
+    let events = daemon
+        .browse(SERVICE_TYPE)
+        .map_err(|e| format!("mDNS browse failed: {e}"))?;
+
+    let deadline = Instant::now() + timeout;
+    let mut last_error = None;
+    let result = loop {
+        let left = deadline.saturating_duration_since(Instant::now());
+        let Ok(event) = events.recv_timeout(left) else {
+            break Err(last_error
+                .unwrap_or_else(|| "VRChat not found. Is OSC enabled in VRChat?".to_string()));
+        };
+        let ServiceEvent::ServiceResolved(service) = event else {
+            continue;
+        };
+        if !service.get_fullname().starts_with(VRCHAT_PREFIX) {
+            continue;
+        }
+        // Prefer IPv4, which is what VRChat's HTTP server listens on.
+        let mut ips: Vec<IpAddr> = service
+            .get_addresses()
+            .iter()
+            .map(|ip| ip.to_ip_addr())
+            .collect();
+        ips.sort_by_key(IpAddr::is_ipv6);
+        for ip in ips {
+            match query_osc_port(SocketAddr::new(ip, service.get_port())) {
+                Ok(port) => return finish(daemon, Ok(port)),
+                Err(e) => last_error = Some(e),
+            }
+        }
+    };
+    finish(daemon, result)
+}
+
+fn finish(daemon: ServiceDaemon, result: Result<u16, String>) -> Result<u16, String> {
+    let _ = daemon.shutdown();
+    result
+}
+
+/// Fetches `HOST_INFO` over plain HTTP/1.0 and returns its `OSC_PORT`.
+fn query_osc_port(addr: SocketAddr) -> Result<u16, String> {
+    let error = |e: std::io::Error| format!("OSCQuery request to {addr} failed: {e}");
+    let mut stream = TcpStream::connect_timeout(&addr, HTTP_TIMEOUT).map_err(error)?;
+    stream.set_read_timeout(Some(HTTP_TIMEOUT)).map_err(error)?;
+    write!(stream, "GET /?HOST_INFO HTTP/1.0\r\nHost: {addr}\r\n\r\n").map_err(error)?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response).map_err(error)?;
+
+    let body = response.split_once("\r\n\r\n").map_or("", |(_, body)| body);
+    let host_info: serde_json::Value =
+        serde_json::from_str(body).map_err(|e| format!("Invalid HOST_INFO from {addr}: {e}"))?;
+    host_info["OSC_PORT"]
+        .as_u64()
+        .and_then(|port| u16::try_from(port).ok())
+        .ok_or_else(|| format!("HOST_INFO from {addr} has no OSC_PORT"))
+}
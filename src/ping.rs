@@ -0,0 +1,179 @@
+//! Round-trip latency to an OSC echo: numbered pings go out to the
+//! destinations, and the receiver matches the echoes coming back to the
+//! listen port.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use rosc::{OscMessage, OscPacket, OscType};
+
+use crate::osc_core::{Shared, bind_socket, send_quiet};
+
+pub const PING_ADDRESS: &str = "/osc_sender/ping";
+const PING_INTERVAL: Duration = Duration::from_secs(1);
+/// Statistics cover this many most recent echoes.
+const PING_WINDOW: usize = 20;
+/// Pings not echoed within this long are given up on.
+const PING_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Default)]
+pub struct Ping {
+    next_seq: i32,
+    /// Pings awaiting their echo, oldest first.
+    pending: VecDeque<(i32, Instant)>,
+    /// Most recent round-trip times, oldest first.
+    rtts: VecDeque<Duration>,
+}
+
+impl Ping {
+    /// Records a new ping and returns the message carrying its number.
+    pub fn send(&mut self) -> OscMessage {
+        let seq = self.next_seq;
+        self.next_seq = seq.wrapping_add(1);
+        let now = Instant::now();
+        self.pending
+            .retain(|&(_, at)| now.duration_since(at) < PING_TIMEOUT);
+        self.pending.push_back((seq, now));
+        OscMessage {
+            addr: PING_ADDRESS.to_string(),
+            args: vec![OscType::Int(seq)],
+        }
+    }
+
+    /// Matches an echoed ping to the one sent. Returns whether `msg` was a
+    /// ping echo at all, pending or not.
+    pub fn receive(&mut self, msg: &OscMessage) -> bool {
+        let (PING_ADDRESS, [OscType::Int(seq)]) = (msg.addr.as_str(), msg.args.as_slice()) else {
+            return false;
+        };
+        if let Some(index) = self.pending.iter().position(|&(sent, _)| sent == *seq) {
+            let (_, at) = self.pending.remove(index).unwrap();
+            if self.rtts.len() == PING_WINDOW {
+                self.rtts.pop_front();
+            }
+            self.rtts.push_back(at.elapsed());
+        }
+        true
+    }
+
+    /// Min, average and max round-trip time over the window.
+    pub fn stats(&self) -> Option<(Duration, Duration, Duration)> {
+        let min = *self.rtts.iter().min()?;
+        let max = *self.rtts.iter().max()?;
+        let avg = self.rtts.iter().sum::<Duration>() / self.rtts.len() as u32;
+        Some((min, avg, max))
+    }
+
+    pub fn clear(&mut self) {
+        self.pending.clear();
+        self.rtts.clear();
+    }
+}
+
+/// Runs until shutdown, pinging every destination once a second while
+/// `ping_enabled` is set. Pings go out with [`send_quiet`], so a sequence
+/// number never spoils the echo match and they don't count as clicks.
+pub fn run_ping(shared: &Shared) {
+    let Some((mut socket, mut bound)) = bind_socket(shared, false) else {
+        return;
+    };
+
+    loop {
+        let (settings, msg) = {
+            let mut state = shared
+                .wake
+                .wait_while(shared.state.lock().unwrap(), |s| {
+                    (!s.settings.ping_enabled || s.halted) && !shared.is_shutdown()
+                })
+                .unwrap();
+            if shared.is_shutdown() {
+                return;
+            }
+            let msg = state.ping.send();
+            (state.settings.clone(), msg)
+        };
+
+        if settings.socket_config() != bound {
+            match bind_socket(shared, false) {
+                Some(rebound) => (socket, bound) = rebound,
+                None => return,
+            }
+        }
+        let _ = send_quiet(
+            shared,
+            &socket,
+            &settings.destinations(),
+            &OscPacket::Message(msg),
+        );
+
+        let _ = shared
+            .wake
+            .wait_timeout_while(shared.state.lock().unwrap(), PING_INTERVAL, |s| {
+                s.settings.ping_enabled && !s.halted && !shared.is_shutdown()
+            })
+            .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::UdpSocket;
+    use std::thread;
+
+    use rosc::decoder;
+
+    use super::*;
+    use crate::osc_core::Settings;
+
+    #[test]
+    fn echoes_are_matched_by_sequence_number() {
+        let mut ping = Ping::default();
+        let first = ping.send();
+        let second = ping.send();
+        assert_eq!(ping.stats(), None);
+
+        // Out of order, duplicated and unrelated messages are all handled.
+        assert!(ping.receive(&second));
+        assert!(ping.receive(&second));
+        assert!(ping.receive(&first));
+        assert!(!ping.receive(&OscMessage {
+            addr: "/avatar/parameters/Jump".to_string(),
+            args: vec![OscType::Int(0)],
+        }));
+
+        assert_eq!(ping.rtts.len(), 2);
+        let (min, avg, max) = ping.stats().unwrap();
+        assert!(min <= avg && avg <= max);
+        assert!(ping.pending.is_empty());
+    }
+
+    #[test]
+    fn pings_go_out_bare_even_with_sequence_numbers_on() {
+        let echo = UdpSocket::bind("127.0.0.1:0").unwrap();
+        echo.set_read_timeout(Some(Duration::from_secs(2))).unwrap();
+        let settings = Settings {
+            dest_ports: vec![echo.local_addr().unwrap().port()],
+            append_sequence: true,
+            ping_enabled: true,
+            ..Settings::default()
+        };
+        let shared = Shared::new(settings);
+
+        let mut buf = [0; 1024];
+        let msg = thread::scope(|scope| {
+            scope.spawn(|| run_ping(&shared));
+            let (len, _) = echo.recv_from(&mut buf).unwrap();
+            shared.request_shutdown();
+            match decoder::decode_udp(&buf[..len]).unwrap().1 {
+                OscPacket::Message(msg) => msg,
+                packet => panic!("not a message: {packet:?}"),
+            }
+        });
+
+        let mut state = shared.state.lock().unwrap();
+        assert!(state.ping.receive(&msg));
+        assert!(state.ping.stats().is_some());
+        assert_eq!((state.sequence, state.packets_sent), (0, 0));
+        assert!(state.log.is_empty());
+    }
+}
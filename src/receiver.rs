@@ -0,0 +1,104 @@
+//! Optional OSC listener, so VRChat's parameter output can confirm that the
+//! sent input actually had an effect.
+
+use std::io;
+use std::net::{Ipv4Addr, SocketAddr, UdpSocket};
+use std::time::{Duration, Instant};
+
+use chrono::Local;
+use rosc::{OscPacket, OscType, decoder};
+
+use crate::osc_core::{AppState, BIND_RETRY_INTERVAL, Direction, LogEntry, Shared};
+
+/// How often a blocked `recv_from` wakes up to check for setting changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Runs until shutdown, listening whenever `listen_enabled` is set and
+/// rebinding when the listen port changes.
+pub fn run_receiver(shared: &Shared) {
+    let mut buf = [0; decoder::MTU];
+
+    loop {
+        let port = {
+            let state = shared
+                .wake
+                .wait_while(shared.state.lock().unwrap(), |s| {
+                    !s.settings.listen_enabled && !shared.is_shutdown()
+                })
+                .unwrap();
+            if shared.is_shutdown() {
+                return;
+            }
+            state.settings.listen_port
+        };
+        let socket = match UdpSocket::bind((Ipv4Addr::UNSPECIFIED, port)).and_then(|socket| {
+            socket
+                .set_read_timeout(Some(POLL_INTERVAL))
+                .map(|()| socket)
+        }) {
+            Ok(socket) => {
+                shared.update(|s| s.listen_error = None);
+                socket
+            }
+            Err(e) => {
+                let mut state = shared.state.lock().unwrap();
+                state.listen_error = Some(format!("Failed to listen on port {port}: {e}"));
+                let _ = shared
+                    .wake
+                    .wait_timeout_while(state, BIND_RETRY_INTERVAL, |s| {
+                        listening_on(shared, s, port)
+                    })
+                    .unwrap();
+                continue;
+            }
+        };
+
+        while listening_on(shared, &shared.state.lock().unwrap(), port) {
+            match socket.recv_from(&mut buf) {
+                Ok((len, from)) => match decoder::decode_udp(&buf[..len]) {
+                    Ok((_, packet)) => log_packet(shared, packet, from),
+                    Err(e) => shared.update(|s| {
+                        s.listen_error = Some(format!("Invalid OSC from {from}: {e:?}"))
+                    }),
+                },
+                Err(e)
+                    if matches!(
+                        e.kind(),
+                        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                    ) => {}
+                Err(e) => shared.update(|s| s.listen_error = Some(format!("Receive failed: {e}"))),
+            }
+        }
+    }
+}
+
+fn listening_on(shared: &Shared, state: &AppState, port: u16) -> bool {
+    state.settings.listen_enabled && state.settings.listen_port == port && !shared.is_shutdown()
+}
+
+/// Logs every message in the packet, bundles included, whose address matches
+/// the listen filter. Ping echoes are timed instead of logged.
+fn log_packet(shared: &Shared, packet: OscPacket, from: SocketAddr) {
+    match packet {
+        OscPacket::Message(msg) => {
+            let mut state = shared.state.lock().unwrap();
+            if state.ping.receive(&msg) || !msg.addr.starts_with(&state.settings.listen_filter) {
+                return;
+            }
+            state.push_log(LogEntry {
+                at: Instant::now(),
+                time: Local::now(),
+                direction: Direction::Received,
+                dry_run: false,
+                address: msg.addr,
+                value: msg.args.into_iter().next().unwrap_or(OscType::Nil),
+                dests: vec![from],
+            });
+        }
+        OscPacket::Bundle(bundle) => {
+            for packet in bundle.content {
+                log_packet(shared, packet, from);
+            }
+        }
+    }
+}
@@ -0,0 +1,134 @@
+//! Resolves a destination hostname, so a LAN machine can be addressed by
+//! name (`my-gaming-pc.local`) instead of an IP that may change.
+
+use std::net::{IpAddr, ToSocketAddrs};
+use std::time::{Duration, Instant};
+
+use crate::osc_core::{AppState, Shared};
+
+/// How often a resolved hostname is looked up again, in case it moved.
+const RESOLVE_INTERVAL: Duration = Duration::from_secs(30);
+/// How soon a failed lookup is retried.
+const RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Runs until shutdown, resolving `dest_host` into `dest_ip` whenever a
+/// hostname is set. Sending holds off while the name doesn't resolve.
+pub fn run_resolver(shared: &Shared) {
+    // The host last looked up, what it resolved to, and when.
+    let mut lookup: Option<(String, Result<IpAddr, String>, Instant)> = None;
+    let mut state = shared.state.lock().unwrap();
+    loop {
+        if shared.is_shutdown() {
+            return;
+        }
+        let host = state.settings.dest_host.trim().to_string();
+        let due = match &lookup {
+            Some((name, result, at)) => *name != host || at.elapsed() >= retry_after(result),
+            None => true,
+        };
+        if !host.is_empty() && due {
+            // A new name holds sending until it resolves, rather than
+            // sending to the old address meanwhile.
+            if lookup.as_ref().is_none_or(|(name, ..)| *name != host) {
+                state.set_host_unresolved(true);
+                shared.wake.notify_all();
+            }
+            drop(state);
+            lookup = Some((host.clone(), resolve(&host), Instant::now()));
+            state = shared.state.lock().unwrap();
+            // The name may have changed during the lookup.
+            continue;
+        }
+
+        let resolved = match lookup.as_ref().filter(|_| !host.is_empty()) {
+            None => {
+                state.host_error = None;
+                state.set_host_unresolved(false);
+                None
+            }
+            Some((_, Ok(ip), _)) => {
+                state.settings.dest_ip = *ip;
+                state.host_error = None;
+                state.set_host_unresolved(false);
+                Some(*ip)
+            }
+            Some((_, Err(e), _)) => {
+                state.host_error = Some(e.clone());
+                state.set_host_unresolved(true);
+                None
+            }
+        };
+        shared.wake.notify_all();
+
+        // Wake for a new name, or when a settings push from the UI put back
+        // an address other than the resolved one.
+        let unchanged = |s: &mut AppState| {
+            s.settings.dest_host.trim() == host
+                && resolved.is_none_or(|ip| s.settings.dest_ip == ip)
+                && !shared.is_shutdown()
+        };
+        state = match &lookup {
+            Some((_, result, at)) if !host.is_empty() => {
+                let timeout = retry_after(result).saturating_sub(at.elapsed());
+                shared
+                    .wake
+                    .wait_timeout_while(state, timeout, unchanged)
+                    .unwrap()
+                    .0
+            }
+            _ => shared.wake.wait_while(state, unchanged).unwrap(),
+        };
+    }
+}
+
+fn retry_after(result: &Result<IpAddr, String>) -> Duration {
+    if result.is_ok() {
+        RESOLVE_INTERVAL
+    } else {
+        RETRY_INTERVAL
+    }
+}
+
+/// The first address `host` resolves to.
+fn resolve(host: &str) -> Result<IpAddr, String> {
+    (host, 0)
+        .to_socket_addrs()
+        .map_err(|e| format!("Couldn't resolve {host}: {e}"))?
+        .next()
+        .map(|addr| addr.ip())
+        .ok_or_else(|| format!("{host} has no addresses"))
+}
+
+/// Whether `host` could be a DNS or mDNS name: dot-separated labels of
+/// letters, digits and inner hyphens.
+pub fn is_valid_hostname(host: &str) -> bool {
+    !host.is_empty()
+        && host.len() <= 253
+        && host.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hostnames_are_validated_by_label() {
+        for host in ["localhost", "my-gaming-pc.local", "a.b-c.example"] {
+            assert!(is_valid_hostname(host), "{host}");
+        }
+        for host in ["", "my pc", "-pc.local", "pc-.local", "pc..local", "pc_1"] {
+            assert!(!is_valid_hostname(host), "{host}");
+        }
+    }
+
+    #[test]
+    fn localhost_resolves_to_loopback() {
+        assert!(resolve("localhost").unwrap().is_loopback());
+    }
+}
@@ -0,0 +1,139 @@
+//! Timed OSC scripts: a JSON list of messages, each sent a delay after the
+//! previous one, played by the sender thread instead of clicking.
+
+use std::fs;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use rosc::{OscMessage, OscPacket, OscType};
+use serde::{Deserialize, Serialize};
+
+use crate::notify::notify;
+use crate::osc_core::{ArgKind, Shared, Sink, is_valid_address, send_packet, sleep_in_session};
+
+/// One step of a script, e.g.
+/// `{"delay_ms": 200, "address": "/input/Jump", "value": 1, "type": "Int"}`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ScriptEvent {
+    /// Wait this long after the previous event (or the start) before sending.
+    #[serde(default)]
+    pub delay_ms: u64,
+    pub address: String,
+    pub value: f32,
+    #[serde(rename = "type", default)]
+    pub kind: ArgKind,
+}
+
+pub struct Script {
+    /// File name, for display.
+    pub name: String,
+    pub events: Vec<ScriptEvent>,
+}
+
+impl Script {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("Failed to read script: {e}"))?;
+        let events: Vec<ScriptEvent> =
+            serde_json::from_str(&text).map_err(|e| format!("Invalid script: {e}"))?;
+        if events.is_empty() {
+            return Err("Script has no events".to_string());
+        }
+        if let Some(index) = events.iter().position(|e| !is_valid_address(&e.address)) {
+            return Err(format!(
+                "Event {} has an invalid address: {:?}",
+                index + 1,
+                events[index].address
+            ));
+        }
+        let name = path
+            .file_name()
+            .map_or_else(String::new, |name| name.to_string_lossy().into_owned());
+        Ok(Self { name, events })
+    }
+
+    pub fn save(path: &Path, events: &[ScriptEvent]) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(events).map_err(|e| e.to_string())?;
+        fs::write(path, json).map_err(|e| format!("Failed to save script: {e}"))
+    }
+
+    /// How long one pass through the script takes.
+    pub fn duration(&self) -> Duration {
+        Duration::from_millis(self.events.iter().map(|e| e.delay_ms).sum())
+    }
+}
+
+/// Sent messages captured as script events, with the time between them.
+pub struct Recording {
+    /// Still capturing. A stopped recording keeps its events for saving and
+    /// replay.
+    pub active: bool,
+    last: Instant,
+    pub events: Vec<ScriptEvent>,
+}
+
+impl Recording {
+    pub fn new() -> Self {
+        Self {
+            active: true,
+            last: Instant::now(),
+            events: Vec::new(),
+        }
+    }
+
+    /// Appends a message by its first argument. Arguments a script can't
+    /// express (strings, blobs, ...) are skipped.
+    pub fn record(&mut self, msg: &OscMessage) {
+        let (value, kind) = match msg.args.first() {
+            Some(OscType::Int(value)) => (*value as f32, ArgKind::Int),
+            Some(OscType::Float(value)) => (*value, ArgKind::Float),
+            Some(OscType::Bool(value)) => (if *value { 1.0 } else { 0.0 }, ArgKind::Bool),
+            _ => return,
+        };
+        let now = Instant::now();
+        self.events.push(ScriptEvent {
+            delay_ms: now.duration_since(self.last).as_millis() as u64,
+            address: msg.addr.clone(),
+            value,
+            kind,
+        });
+        self.last = now;
+    }
+}
+
+impl Default for Recording {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Plays the script until it ends, or forever when `looping`, returning early
+/// when sending stops. Turns sending off after a single pass.
+pub(crate) fn play_script(
+    shared: &Shared,
+    sink: &dyn Sink,
+    dests: &[SocketAddr],
+    script: &Script,
+    looping: bool,
+) {
+    loop {
+        for event in &script.events {
+            if !sleep_in_session(shared, Duration::from_millis(event.delay_ms)) {
+                return;
+            }
+            let packet = OscPacket::Message(OscMessage {
+                addr: event.address.clone(),
+                args: vec![event.kind.arg(event.value)],
+            });
+            let _ = send_packet(shared, sink, dests, &packet);
+        }
+        // A script of zero delays would spin without ever yielding.
+        if !looping || script.duration().is_zero() {
+            shared.update(|s| {
+                s.set_sending(false);
+                notify(&s.settings, "Stopped: script finished");
+            });
+            return;
+        }
+    }
+}
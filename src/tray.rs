@@ -0,0 +1,108 @@
+//! System tray icon, so the window can be hidden while sending keeps running.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use osc_sender::osc_core::{AppState, Shared};
+use tray_icon::menu::{Menu, MenuEvent, MenuItem};
+use tray_icon::{Icon, TrayIcon, TrayIconBuilder};
+
+const ICON_SIZE: u32 = 32;
+
+/// Owns the tray icon. Dropping it removes the icon and its menu handler.
+pub struct Tray {
+    _icon: TrayIcon,
+    /// Whether the window is shown, shared with the menu handler so hiding
+    /// from the app keeps "Show/Hide window" in step.
+    visible: Arc<AtomicBool>,
+    /// Set by the "Quit" item, so the close request it sends isn't turned
+    /// into a hide.
+    quitting: Arc<AtomicBool>,
+}
+
+impl Tray {
+    pub fn new(ctx: &egui::Context, shared: Arc<Shared>) -> Result<Self, String> {
+        let show_hide = MenuItem::new("Show/Hide window", true, None);
+        let toggle = MenuItem::new("Toggle sending", true, None);
+        let quit = MenuItem::new("Quit", true, None);
+
+        let menu = Menu::new();
+        menu.append_items(&[&show_hide, &toggle, &quit])
+            .map_err(|e| format!("Failed to build tray menu: {e}"))?;
+
+        let icon = TrayIconBuilder::new()
+            .with_menu(Box::new(menu))
+            .with_tooltip("OSC Sender")
+            .with_icon(icon()?)
+            .build()
+            .map_err(|e| format!("Tray icon unavailable: {e}"))?;
+
+        let (show_hide, toggle, quit) = (
+            show_hide.id().clone(),
+            toggle.id().clone(),
+            quit.id().clone(),
+        );
+        let visible = Arc::new(AtomicBool::new(true));
+        let quitting = Arc::new(AtomicBool::new(false));
+        let (handler_visible, handler_quitting) = (visible.clone(), quitting.clone());
+        let ctx = ctx.clone();
+        // Like the hotkey handler this runs on the event loop thread, so it
+        // still fires while the viewport is hidden and not repainting.
+        MenuEvent::set_event_handler(Some(move |event: MenuEvent| {
+            if event.id == show_hide {
+                let show = !handler_visible.fetch_xor(true, Ordering::SeqCst);
+                ctx.send_viewport_cmd(egui::ViewportCommand::Visible(show));
+                if show {
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+                }
+                ctx.request_repaint();
+            } else if event.id == toggle {
+                shared.update(AppState::toggle_sending);
+                ctx.request_repaint();
+            } else if event.id == quit {
+                handler_quitting.store(true, Ordering::SeqCst);
+                // A hidden viewport may never process the close command.
+                ctx.send_viewport_cmd(egui::ViewportCommand::Visible(true));
+                ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                ctx.request_repaint();
+            }
+        }));
+
+        Ok(Self {
+            _icon: icon,
+            visible,
+            quitting,
+        })
+    }
+
+    /// Hides the window; the tray menu brings it back.
+    pub fn hide_window(&self, ctx: &egui::Context) {
+        self.visible.store(false, Ordering::SeqCst);
+        ctx.send_viewport_cmd(egui::ViewportCommand::Visible(false));
+    }
+
+    pub fn is_quitting(&self) -> bool {
+        self.quitting.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for Tray {
+    fn drop(&mut self) {
+        MenuEvent::set_event_handler(None::<fn(MenuEvent)>);
+    }
+}
+
+/// A plain filled circle, so no image asset or decoder is needed.
+fn icon() -> Result<Icon, String> {
+    let center = (ICON_SIZE as f32 - 1.0) / 2.0;
+    let radius = ICON_SIZE as f32 / 2.0 - 1.0;
+    let mut rgba = Vec::with_capacity((ICON_SIZE * ICON_SIZE * 4) as usize);
+    for y in 0..ICON_SIZE {
+        for x in 0..ICON_SIZE {
+            let distance = (x as f32 - center).hypot(y as f32 - center);
+            let alpha = if distance <= radius { 255 } else { 0 };
+            rgba.extend_from_slice(&[0x3c, 0xb3, 0x71, alpha]);
+        }
+    }
+    Icon::from_rgba(rgba, ICON_SIZE, ICON_SIZE).map_err(|e| format!("Invalid tray icon: {e}"))
+}
@@ -0,0 +1,131 @@
+//! Watches for a running VRChat process, so sending can hold off while
+//! there's nothing to receive it, and handles the `--osc` option of its Quick
+//! Launcher.
+
+use std::ffi::OsStr;
+use std::time::Duration;
+
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System};
+
+use crate::osc_core::Shared;
+
+const PROCESS_NAME: &str = if cfg!(windows) {
+    "VRChat.exe"
+} else {
+    "VRChat"
+};
+
+/// How often the process list is rescanned.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Runs until shutdown, updating `vrchat_running` and pausing sending while
+/// VRChat is missing and `only_when_vrchat` is on.
+pub fn run_vrchat_watch(shared: &Shared) {
+    let mut system = System::new();
+    let mut running = is_running(&mut system);
+    let mut state = shared.state.lock().unwrap();
+    loop {
+        if shared.is_shutdown() {
+            return;
+        }
+        state.vrchat_running = Some(running);
+        let only = state.settings.only_when_vrchat;
+        state.set_vrchat_missing(only && !running);
+        shared.wake.notify_all();
+
+        // Toggling the option applies at once instead of on the next scan.
+        let (next, timeout) = shared
+            .wake
+            .wait_timeout_while(state, POLL_INTERVAL, |s| {
+                s.settings.only_when_vrchat == only && !shared.is_shutdown()
+            })
+            .unwrap();
+        state = next;
+        if timeout.timed_out() {
+            drop(state);
+            running = is_running(&mut system);
+            state = shared.state.lock().unwrap();
+        }
+    }
+}
+
+fn is_running(system: &mut System) -> bool {
+    system.refresh_processes_specifics(ProcessesToUpdate::All, true, ProcessRefreshKind::nothing());
+    system
+        .processes_by_exact_name(OsStr::new(PROCESS_NAME))
+        .next()
+        .is_some()
+}
+
+/// Fills in the Quick Launcher template, or `None` if it lacks `{port}`.
+/// `{listen_port}` is the companion port VRChat sends its output to, which
+/// need not be `port + 1`.
+pub fn render_quick_launcher(template: &str, port: u16, listen_port: u16) -> Option<String> {
+    template.contains("{port}").then(|| {
+        template
+            .replace("{listen_port}", &listen_port.to_string())
+            .replace("{port}", &port.to_string())
+    })
+}
+
+/// Reads the ports back out of a Quick Launcher value such as
+/// `9000:localhost:9001` (optionally prefixed by `--osc=`): the port VRChat
+/// receives on, which we send to, and the one it sends to, which we listen on.
+pub fn parse_quick_launcher(value: &str) -> Option<(u16, u16)> {
+    let value = value.trim();
+    let value = value.strip_prefix("--osc=").unwrap_or(value);
+    let [dest_port, host, listen_port] = value.split(':').collect::<Vec<_>>()[..] else {
+        return None;
+    };
+    if host.trim().is_empty() {
+        return None;
+    }
+    Some((
+        dest_port.trim().parse().ok()?,
+        listen_port.trim().parse().ok()?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quick_launcher_ports_are_read_back() {
+        assert_eq!(
+            parse_quick_launcher("9000:localhost:9001"),
+            Some((9000, 9001))
+        );
+        assert_eq!(
+            parse_quick_launcher(" --osc=9000:127.0.0.1:9002 "),
+            Some((9000, 9002))
+        );
+        assert_eq!(
+            parse_quick_launcher("9000: localhost :9001"),
+            Some((9000, 9001))
+        );
+        for value in [
+            "",
+            "9000::9001",
+            "9000:localhost",
+            "osc:localhost:9001",
+            "9000:localhost:70000",
+            "9000:localhost:9001:9002",
+            "--osc=9000:localhost:9001:extra",
+        ] {
+            assert_eq!(parse_quick_launcher(value), None, "{value}");
+        }
+    }
+
+    #[test]
+    fn quick_launcher_template_needs_the_port() {
+        assert_eq!(
+            render_quick_launcher("{port}:localhost:{listen_port}", 9000, 9005).as_deref(),
+            Some("9000:localhost:9005")
+        );
+        assert_eq!(
+            render_quick_launcher("localhost:{listen_port}", 9000, 9005),
+            None
+        );
+    }
+}